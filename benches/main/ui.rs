@@ -31,385 +31,525 @@ pub fn draw_results_list(c: &mut Criterion) {
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{f016}',
                 color: "#7e8e91",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/README.md".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{f48a}',
                 color: "#dddddd",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/re.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/io.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/gc.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/uu.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/nt.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/dis.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/imp.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/bdb.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/abc.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/cgi.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/bz2.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/grp.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/ast.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/csv.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/pdb.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/pwd.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/ssl.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/tty.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/nis.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/pty.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/cmd.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/tests/utils.py".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/pyproject.toml".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e6b2}',
                 color: "#9c4221",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/MAINTAINERS.md".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{f48a}',
                 color: "#dddddd",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/enum.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/hmac.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/uuid.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/glob.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/_ast.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/_csv.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/code.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/spwd.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/_msi.pyi".to_string(),
             value: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
             icon: Some(FileIcon {
                 icon: '\u{e606}',
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         },
         Entry {
             name: "typeshed/stdlib/time.pyi".to_string(),
@@ -420,8 +560,12 @@ pub fn draw_results_list(c: &mut Criterion) {
                 color: "#ffbc03",
             }),
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
             name_match_ranges: Some(into_ranges(&[0, 1, 2, 3])),
             value_match_ranges: None,
+            name_styles: None,
         },
     ];
 
@@ -110,6 +110,12 @@ pub enum Action {
     /// Signal an error with the given message.
     #[serde(skip)]
     Error(String),
+    /// Warn the user with the given message.
+    #[serde(skip)]
+    Warning(String),
+    /// Dismiss the most recent notification.
+    #[serde(alias = "dismiss_notification")]
+    DismissNotification,
     /// No operation.
     #[serde(skip)]
     NoOp,
@@ -120,4 +126,31 @@ pub enum Action {
     /// Toggle the remote control in `send to channel` mode.
     #[serde(alias = "toggle_send_to_channel")]
     ToggleSendToChannel,
+    /// Re-run the current channel's source command.
+    #[serde(alias = "reload_source")]
+    ReloadSource,
+    /// Forget the cached previews and preview the current entry again.
+    #[serde(alias = "invalidate_preview_cache")]
+    InvalidatePreviewCache,
+    /// Go back to the previous channel in the channel history.
+    #[serde(alias = "go_back")]
+    GoBack,
+    /// Go forward to the next channel in the channel history.
+    #[serde(alias = "go_forward")]
+    GoForward,
+    /// Replace the input with the previous query of the current channel.
+    #[serde(alias = "previous_query")]
+    PreviousQuery,
+    /// Replace the input with the next query of the current channel.
+    #[serde(alias = "next_query")]
+    NextQuery,
+    /// Forget the frecency of the entry under the cursor.
+    #[serde(alias = "forget_entry")]
+    ForgetEntry,
+    /// Switch to the next matching mode (fuzzy, substring, prefix, ...).
+    #[serde(alias = "cycle_matching_mode")]
+    CycleMatchingMode,
+    /// Run the current channel's custom action with the given name.
+    #[serde(skip)]
+    CustomAction(String),
 }
@@ -1,12 +1,15 @@
+use std::process::Stdio;
+
 use rustc_hash::FxHashSet;
 
 use anyhow::Result;
-use tokio::sync::mpsc;
-use tracing::{debug, trace};
+use tokio::{process::Command, sync::mpsc};
+use tracing::{debug, trace, warn};
 
 use crate::{
     action::Action,
     channels::{
+        actions::ExecutionMode,
         entry::Entry,
         prototypes::{Cable, ChannelPrototype},
     },
@@ -15,6 +18,7 @@ use crate::{
     keymap::Keymap,
     render::{render, RenderingTask, UiState},
     television::{Mode, Television},
+    utils::command::{foreground_shell_command, shell_command},
 };
 
 #[allow(clippy::struct_excessive_bools)]
@@ -64,6 +68,7 @@ impl AppOptions {
 }
 
 /// The main application struct that holds the state of the application.
+#[allow(clippy::struct_excessive_bools)]
 pub struct App {
     keymap: Keymap,
     /// The television instance that handles channels and entries.
@@ -98,6 +103,11 @@ pub struct App {
     /// Render task handle
     render_task: Option<tokio::task::JoinHandle<Result<()>>>,
     options: AppOptions,
+    /// Whether the application is running without a terminal (see
+    /// `run_headless`).
+    headless: bool,
+    /// Whether the output is believed to be a TTY.
+    is_output_tty: bool,
 }
 
 /// The outcome of an action.
@@ -105,6 +115,8 @@ pub struct App {
 pub enum ActionOutcome {
     Entries(FxHashSet<Entry>),
     Input(String),
+    /// Replace the application with the given command.
+    Become(String),
     None,
 }
 
@@ -112,6 +124,8 @@ pub enum ActionOutcome {
 #[derive(Debug)]
 pub struct AppOutput {
     pub selected_entries: Option<FxHashSet<Entry>>,
+    /// A command the application should be replaced with once exited.
+    pub become_command: Option<String>,
 }
 
 impl From<ActionOutcome> for AppOutput {
@@ -119,14 +133,21 @@ impl From<ActionOutcome> for AppOutput {
         match outcome {
             ActionOutcome::Entries(entries) => Self {
                 selected_entries: Some(entries),
+                become_command: None,
             },
             ActionOutcome::Input(input) => Self {
                 selected_entries: Some(FxHashSet::from_iter([Entry::new(
                     input,
                 )])),
+                become_command: None,
+            },
+            ActionOutcome::Become(command) => Self {
+                selected_entries: None,
+                become_command: Some(command),
             },
             ActionOutcome::None => Self {
                 selected_entries: None,
+                become_command: None,
             },
         }
     }
@@ -177,6 +198,8 @@ impl App {
             ui_state_tx,
             render_task: None,
             options,
+            headless: false,
+            is_output_tty: false,
         }
     }
 
@@ -199,28 +222,11 @@ impl App {
         is_output_tty: bool,
         headless: bool,
     ) -> Result<AppOutput> {
-        // Event loop
+        self.headless = headless;
+        self.is_output_tty = is_output_tty;
         if !headless {
-            debug!("Starting backend event loop");
-            let event_loop = EventLoop::new(self.options.tick_rate);
-            self.event_rx = event_loop.rx;
-            self.event_abort_tx = event_loop.abort_tx;
-        }
-
-        // Rendering loop
-        if !headless {
-            debug!("Starting rendering loop");
-            let (render_tx, render_rx) = mpsc::unbounded_channel();
-            self.render_tx = render_tx.clone();
-            let ui_state_tx = self.ui_state_tx.clone();
-            let action_tx_r = self.action_tx.clone();
-            self.render_task = Some(tokio::spawn(async move {
-                render(render_rx, action_tx_r, ui_state_tx, is_output_tty)
-                    .await
-            }));
-            self.action_tx
-                .send(Action::Render)
-                .expect("Unable to send init render action.");
+            self.start_event_loop();
+            self.start_rendering_loop();
         }
 
         // Main loop
@@ -262,6 +268,9 @@ impl App {
             }
 
             if self.should_quit {
+                // make sure no source or preview process outlives the app
+                self.television.shutdown();
+
                 // send a termination signal to the event loop
                 if !headless {
                     self.event_abort_tx.send(())?;
@@ -277,6 +286,108 @@ impl App {
         }
     }
 
+    fn start_event_loop(&mut self) {
+        debug!("Starting backend event loop");
+        let event_loop = EventLoop::new(self.options.tick_rate);
+        self.event_rx = event_loop.rx;
+        self.event_abort_tx = event_loop.abort_tx;
+    }
+
+    fn start_rendering_loop(&mut self) {
+        debug!("Starting rendering loop");
+        let (render_tx, render_rx) = mpsc::unbounded_channel();
+        self.render_tx = render_tx.clone();
+        let ui_state_tx = self.ui_state_tx.clone();
+        let action_tx_r = self.action_tx.clone();
+        let is_output_tty = self.is_output_tty;
+        self.render_task = Some(tokio::spawn(async move {
+            render(render_rx, action_tx_r, ui_state_tx, is_output_tty).await
+        }));
+        self.action_tx
+            .send(Action::Render)
+            .expect("Unable to send init render action.");
+    }
+
+    /// Hand the terminal over to the given command and take it back once
+    /// the command exits.
+    async fn execute_in_foreground(&mut self, command: &str) -> Result<()> {
+        debug!("Executing {:?} in the foreground", command);
+        if !self.headless {
+            // stop listening to key events and restore the terminal
+            self.event_abort_tx.send(())?;
+            self.render_tx.send(RenderingTask::Quit)?;
+            if let Some(rendering_task) = self.render_task.take() {
+                rendering_task.await??;
+            }
+        }
+
+        let mut cmd = Command::from(foreground_shell_command(false));
+        cmd.arg(command);
+        if self.headless {
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+        } else {
+            // keep our own output clean if something is reading it
+            if !self.is_output_tty {
+                cmd.stdout(std::io::stderr());
+            }
+            // the user should be able to interact with the command even
+            // if our stdin was piped
+            #[cfg(unix)]
+            {
+                use std::io::IsTerminal;
+                if !std::io::stdin().is_terminal() {
+                    if let Ok(tty) = std::fs::File::open("/dev/tty") {
+                        cmd.stdin(tty);
+                    }
+                }
+            }
+        }
+        match cmd.status().await {
+            Ok(status) if !status.success() => {
+                warn!("Command {:?} exited with {}", command, status);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                self.action_tx.send(Action::Error(format!(
+                    "Failed to execute {command:?}: {e}"
+                )))?;
+            }
+        }
+
+        if !self.headless {
+            self.start_event_loop();
+            self.start_rendering_loop();
+        }
+        Ok(())
+    }
+
+    /// Run the given command in the background.
+    fn fork(&self, command: &str) -> Result<()> {
+        debug!("Forking {:?}", command);
+        match Command::from(shell_command(false))
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(mut child) => {
+                // reap the process once it exits
+                tokio::spawn(async move {
+                    let _ = child.wait().await;
+                });
+            }
+            Err(e) => {
+                self.action_tx.send(Action::Error(format!(
+                    "Failed to execute {command:?}: {e}"
+                )))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Run the application in headless mode.
     ///
     /// This function will start the event loop and handle all actions that are sent to the
@@ -299,8 +410,15 @@ impl App {
     fn convert_event_to_action(&self, event: Event<Key>) -> Option<Action> {
         let action = match event {
             Event::Input(keycode) => {
+                // channel specific actions take precedence over keybindings
+                if let Some(action) =
+                    self.television.custom_action_for_key(&keycode)
+                {
+                    debug!("Channel action found: {action:?}");
+                    action
+                }
                 // get action based on keybindings
-                if let Some(action) = self.keymap.get(&keycode) {
+                else if let Some(action) = self.keymap.get(&keycode) {
                     debug!("Keybinding found: {action:?}");
                     action.clone()
                 } else {
@@ -367,6 +485,9 @@ impl App {
                         if self.television.mode == Mode::RemoteControl {
                             self.action_tx
                                 .send(Action::ToggleRemoteControl)?;
+                        } else if self.television.mode == Mode::SendToChannel {
+                            self.action_tx
+                                .send(Action::ToggleSendToChannel)?;
                         } else {
                             self.should_quit = true;
                             self.render_tx.send(RenderingTask::Quit)?;
@@ -396,6 +517,27 @@ impl App {
                             self.television.current_pattern.clone(),
                         ));
                     }
+                    Action::CustomAction(ref name) => {
+                        if let Some((mode, command)) =
+                            self.television.format_custom_action(name)
+                        {
+                            match mode {
+                                ExecutionMode::Execute => {
+                                    self.execute_in_foreground(&command)
+                                        .await?;
+                                }
+                                ExecutionMode::Fork => self.fork(&command)?,
+                                ExecutionMode::Become => {
+                                    self.should_quit = true;
+                                    if !self.render_tx.is_closed() {
+                                        self.render_tx
+                                            .send(RenderingTask::Quit)?;
+                                    }
+                                    return Ok(ActionOutcome::Become(command));
+                                }
+                            }
+                        }
+                    }
                     Action::ClearScreen => {
                         self.render_tx.send(RenderingTask::ClearScreen)?;
                     }
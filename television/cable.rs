@@ -3,11 +3,12 @@ use std::path::PathBuf;
 use rustc_hash::FxHashMap;
 
 use anyhow::Result;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 use crate::{
     channels::prototypes::{Cable, ChannelPrototype},
     config::get_config_dir,
+    keymap::Keymap,
 };
 
 /// Just a proxy struct to deserialize prototypes
@@ -41,6 +42,15 @@ const DEFAULT_CABLE_CHANNELS: &str =
 ///   └── windows_channels.toml
 /// ```
 pub fn load_cable() -> Result<Cable> {
+    load_cable_with_errors().map(|(cable, _)| cable)
+}
+
+/// Load the cable configuration from the config directory (see
+/// `load_cable`), along with the errors met while reading the cable files
+/// so that they can be reported to the user.
+///
+/// Files that can't be read or parsed are skipped.
+pub fn load_cable_with_errors() -> Result<(Cable, Vec<String>)> {
     let config_dir = get_config_dir();
 
     // list all files in the config directory
@@ -61,25 +71,40 @@ pub fn load_cable() -> Result<Cable> {
         toml::from_str::<CableSpec>(DEFAULT_CABLE_CHANNELS)
             .expect("Failed to parse default cable channels");
 
+    let mut errors = Vec::new();
     let prototypes = file_paths.iter().fold(
         Vec::<ChannelPrototype>::new(),
         |mut acc, p| {
-            match toml::from_str::<CableSpec>(
-                &std::fs::read_to_string(p)
-                    .expect("Unable to read configuration file"),
-            ) {
+            match std::fs::read_to_string(p)
+                .map_err(anyhow::Error::from)
+                .and_then(|s| Ok(toml::from_str::<CableSpec>(&s)?))
+            {
                 Ok(pts) => acc.extend(pts.prototypes),
                 Err(e) => {
                     error!(
                         "Failed to parse cable channel file {:?}: {}",
                         p, e
                     );
+                    errors.push(format!(
+                        "Failed to parse cable channel file {}: {e}",
+                        p.display()
+                    ));
                 }
             }
             acc
         },
     );
 
+    for prototype in &prototypes {
+        if let Err(e) = prototype.watch_interval() {
+            error!("Invalid cable channel {:?}: {}", prototype.name, e);
+            errors.push(format!(
+                "Invalid cable channel {}: {e}",
+                prototype.name
+            ));
+        }
+    }
+
     debug!("Loaded {} custom cable channels", prototypes.len());
     if prototypes.is_empty() {
         debug!("No custom cable channels found");
@@ -94,7 +119,31 @@ pub fn load_cable() -> Result<Cable> {
     {
         cable_channels.insert(prototype.name.clone(), prototype);
     }
-    Ok(Cable(cable_channels))
+    Ok((Cable(cable_channels), errors))
+}
+
+/// List the custom actions whose key is also bound to a global action.
+///
+/// Channel actions take precedence over keybindings, so such an action
+/// would silently shadow e.g. `ctrl-c` or `enter` in its channel.
+pub fn shadowed_keybindings(cable: &Cable, keymap: &Keymap) -> Vec<String> {
+    let mut prototypes = cable.values().collect::<Vec<_>>();
+    prototypes.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut warnings = Vec::new();
+    for prototype in prototypes {
+        for (name, action) in &prototype.actions {
+            if let Some(global) = keymap.get(&action.key) {
+                let warning = format!(
+                    "Action {name:?} of cable channel {} is bound to {}, \
+                     which shadows the {global:?} keybinding",
+                    prototype.name, action.key
+                );
+                warn!("{}", warning);
+                warnings.push(warning);
+            }
+        }
+    }
+    warnings
 }
 
 fn is_cable_file_format<P>(p: P) -> bool
@@ -113,10 +162,160 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channels::entry::Entry;
+    use crate::previewer::Config as PreviewerConfig;
 
     #[test]
     fn test_is_cable_file() {
         let path = std::path::Path::new("cable_channels.toml");
         assert!(is_cable_file_format(path));
     }
+
+    #[test]
+    fn test_invalid_watch_intervals() {
+        let spec = toml::from_str::<CableSpec>(
+            r#"
+            [[cable_channel]]
+            name = "infinite"
+            source_command = "ls"
+            watch = inf
+
+            [[cable_channel]]
+            name = "nan"
+            source_command = "ls"
+            watch = nan
+
+            [[cable_channel]]
+            name = "disabled"
+            source_command = "ls"
+            watch = -1.0
+
+            [[cable_channel]]
+            name = "valid"
+            source_command = "ls"
+            watch = 0.5
+            "#,
+        )
+        .unwrap();
+
+        let intervals = spec
+            .prototypes
+            .iter()
+            .map(ChannelPrototype::watch_interval)
+            .collect::<Vec<_>>();
+        assert!(intervals[0].is_err());
+        assert!(intervals[1].is_err());
+        assert_eq!(intervals[2], Ok(None));
+        assert_eq!(
+            intervals[3],
+            Ok(Some(std::time::Duration::from_millis(500)))
+        );
+    }
+
+    #[test]
+    fn test_channel_previewer_overrides() {
+        let spec = toml::from_str::<CableSpec>(
+            r#"
+            [[cable_channel]]
+            name = "docker-images"
+            source_command = "docker image list"
+            preview.command = "docker image inspect {0}"
+            job_timeout = 2000
+            "#,
+        )
+        .unwrap();
+
+        let config = spec.prototypes[0].previewer_config(
+            PreviewerConfig::default()
+                .debounce(std::time::Duration::from_millis(50)),
+        );
+        assert_eq!(
+            config,
+            PreviewerConfig::default()
+                .job_timeout(std::time::Duration::from_millis(2000))
+                .debounce(std::time::Duration::from_millis(50))
+        );
+    }
+
+    #[test]
+    fn test_channel_query_debounce_override() {
+        let spec = toml::from_str::<CableSpec>(
+            r#"
+            [[cable_channel]]
+            name = "live-grep"
+            source_command = "rg {q}"
+            query_debounce = 300
+
+            [[cable_channel]]
+            name = "files"
+            source_command = "fd"
+            "#,
+        )
+        .unwrap();
+
+        let default = std::time::Duration::from_millis(150);
+        assert_eq!(
+            spec.prototypes[0].query_debounce(default),
+            std::time::Duration::from_millis(300)
+        );
+        assert_eq!(spec.prototypes[1].query_debounce(default), default);
+    }
+
+    #[test]
+    fn test_docker_images_run_action() {
+        let spec =
+            toml::from_str::<CableSpec>(DEFAULT_CABLE_CHANNELS).unwrap();
+        let prototype = spec
+            .prototypes
+            .iter()
+            .find(|p| p.name == "docker-images")
+            .unwrap();
+        let actions = prototype.resolved_actions();
+        let entry = Entry::new("1a2b\tnginx:latest\t12MB".to_string());
+        assert_eq!(
+            actions["run"].format_command(&entry, &[]),
+            "docker run -it --rm '1a2b'"
+        );
+    }
+
+    #[test]
+    fn test_shadowed_keybindings() {
+        let keymap = Keymap::from(
+            &crate::config::default_config_from_file()
+                .unwrap()
+                .keybindings,
+        );
+        let default_prototypes =
+            toml::from_str::<CableSpec>(DEFAULT_CABLE_CHANNELS).unwrap();
+        let cable = Cable(
+            default_prototypes
+                .prototypes
+                .into_iter()
+                .map(|p| (p.name.clone(), p))
+                .collect(),
+        );
+        assert!(shadowed_keybindings(&cable, &keymap).is_empty());
+
+        let spec = toml::from_str::<CableSpec>(
+            r#"
+            [[cable_channel]]
+            name = "files"
+            source_command = "fd -t f"
+
+            [cable_channel.actions.copy]
+            command = "cp {0} /tmp"
+            key = "ctrl-c"
+            "#,
+        )
+        .unwrap();
+        let cable = Cable(
+            spec.prototypes
+                .into_iter()
+                .map(|p| (p.name.clone(), p))
+                .collect(),
+        );
+        let warnings = shadowed_keybindings(&cable, &keymap);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("\"copy\""));
+    }
 }
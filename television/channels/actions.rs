@@ -0,0 +1,162 @@
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    channels::{entry::Entry, preview::DEFAULT_DELIMITER},
+    config::parse_key,
+    event::Key,
+    utils::strings::{format_entries, format_string},
+};
+
+/// How a custom action's command should be run.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    /// Hand the terminal over to the command and come back to the
+    /// application once it exits.
+    #[default]
+    Execute,
+    /// Run the command in the background without leaving the application.
+    Fork,
+    /// Exit the application and replace it with the command.
+    Become,
+}
+
+/// A custom action declared by a cable channel.
+///
+/// # Example
+/// ```toml
+/// [[cable_channel]]
+/// name = "git-branch"
+/// source_command = "git branch --format=\"%(refname:short)\""
+///
+/// [cable_channel.actions.checkout]
+/// command = "git checkout {}"
+/// key = "ctrl-o"
+/// mode = "become"
+/// ```
+///
+/// The command is formatted using the same placeholders as the preview
+/// command (`{}`, `{0}`, etc.) which refer to the entry under the cursor.
+/// Entries are split using the channel's preview delimiter unless the action
+/// sets its own `delimiter`.
+/// Additionally, `{+}` is replaced by all selected entries (or the entry under
+/// the cursor if none are selected).
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ActionSpec {
+    pub command: String,
+    #[serde(deserialize_with = "deserialize_key")]
+    pub key: Key,
+    #[serde(default)]
+    pub mode: ExecutionMode,
+    #[serde(default)]
+    pub delimiter: Option<String>,
+}
+
+fn deserialize_key<'de, D>(deserializer: D) -> Result<Key, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    parse_key(&raw).map_err(serde::de::Error::custom)
+}
+
+impl ActionSpec {
+    pub fn new(command: &str, key: Key, mode: ExecutionMode) -> Self {
+        Self {
+            command: command.to_string(),
+            key,
+            mode,
+            delimiter: None,
+        }
+    }
+
+    /// Format the action's command for the entry under the cursor and the
+    /// currently selected entries.
+    pub fn format_command(
+        &self,
+        current: &Entry,
+        selected: &[Entry],
+    ) -> String {
+        let formatted = format_string(
+            &self.command,
+            &current.name,
+            self.delimiter.as_deref().unwrap_or(DEFAULT_DELIMITER),
+        );
+        if selected.is_empty() {
+            format_entries(&formatted, &[&current.name])
+        } else {
+            format_entries(
+                &formatted,
+                &selected.iter().map(|e| &e.name).collect::<Vec<_>>(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_deserialize_actions() {
+        let actions: BTreeMap<String, ActionSpec> = toml::from_str(
+            r#"
+            [checkout]
+            command = "git checkout {}"
+            key = "ctrl-o"
+            mode = "become"
+
+            [delete]
+            command = "git branch -D {+}"
+            key = "ctrl-d"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            actions["checkout"],
+            ActionSpec::new(
+                "git checkout {}",
+                Key::Ctrl('o'),
+                ExecutionMode::Become
+            )
+        );
+        assert_eq!(actions["delete"].mode, ExecutionMode::Execute);
+    }
+
+    #[test]
+    fn test_deserialize_invalid_key() {
+        let result = toml::from_str::<ActionSpec>(
+            r#"
+            command = "echo {}"
+            key = "ctrl-<"
+            "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_format_command_current_entry() {
+        let action =
+            ActionSpec::new("echo {} {+}", Key::Enter, ExecutionMode::Fork);
+        assert_eq!(
+            action.format_command(&Entry::new("a b".to_string()), &[]),
+            "echo 'a b' 'a b'"
+        );
+    }
+
+    #[test]
+    fn test_format_command_selected_entries() {
+        let action = ActionSpec::new(
+            "rm {+}; echo {0}",
+            Key::Enter,
+            ExecutionMode::Fork,
+        );
+        let selected = [Entry::new("b".to_string())];
+        assert_eq!(
+            action.format_command(&Entry::new("a x".to_string()), &selected),
+            "rm 'b'; echo 'a'"
+        );
+    }
+}
@@ -1,25 +1,205 @@
-use std::collections::HashSet;
-use std::io::{BufRead, BufReader};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Display, Formatter};
 use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use anyhow::Result;
+use parking_lot::Mutex;
 use rustc_hash::{FxBuildHasher, FxHashSet};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
+    process::{ChildStdin, Command},
+};
 use tracing::debug;
 
 use crate::channels::{
-    entry::Entry, preview::PreviewCommand, prototypes::ChannelPrototype,
+    actions::ActionSpec, entry::Entry, fields::Fields,
+    preview::PreviewCommand, prototypes::ChannelPrototype,
 };
+use crate::event::Key;
+use crate::frecency::Frecency;
 use crate::matcher::Matcher;
-use crate::matcher::{config::Config, injector::Injector};
-use crate::utils::command::shell_command;
+use crate::matcher::{
+    config::Config, injector::Injector, promotion::PromotionMode,
+};
+use crate::utils::{
+    ansi::{parse_ansi, strip_ansi, StyledRange},
+    command::shell_command,
+    process::ProcessTracker,
+};
 
-use crate::utils::strings::format_string;
+use crate::utils::strings::{
+    format_entries, format_query, format_string, EMPTY_STRING,
+    QUERY_PLACEHOLDER, SELECTED_ENTRIES_PLACEHOLDER,
+};
 
 pub struct Channel {
     pub name: String,
-    matcher: Matcher<String>,
+    /// Entries are stored as the raw bytes produced by the source command so
+    /// that they can be output as is, even if they aren't valid UTF-8.
+    matcher: Matcher<Vec<u8>>,
     pub preview_command: Option<PreviewCommand>,
     selected_entries: FxHashSet<Entry>,
-    crawl_handle: tokio::task::JoinHandle<()>,
+    /// The task running the source command, unless it wasn't run at all
+    /// (see `Source::with_query`).
+    crawl_handle: Option<tokio::task::JoinHandle<()>>,
+    processes: ProcessTracker,
+    source: Source,
+    /// How the source command last failed, if it did (see `source_error`).
+    source_error: Arc<Mutex<Option<SourceError>>>,
+    /// The interval at which the source should be reloaded, if any.
+    watch: Option<Duration>,
+    /// When the source was last (re)loaded.
+    loaded_at: Instant,
+    /// The query the source command is run with (see `Source::is_dynamic`).
+    query: String,
+    /// When the query last changed if the source wasn't re-run since.
+    query_changed_at: Option<Instant>,
+    /// How long the query has to stay untouched before the source is re-run.
+    query_debounce: Duration,
+    actions: BTreeMap<String, ActionSpec>,
+    /// Whether the source was stopped before it was done loading (see
+    /// `pause`).
+    interrupted: bool,
+    /// How entries are ranked by frecency, if at all.
+    frecency_mode: Option<PromotionMode>,
+    frecency: Option<Frecency>,
+}
+
+/// Why a channel's source command failed to produce its entries.
+///
+/// This is reported to the user in place of the entries rather than
+/// loading the command's stderr as entries.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SourceError {
+    /// The source command couldn't be run at all.
+    Spawn(String),
+    /// The source command exited with a non-zero code, or was killed by a
+    /// signal if there's no code.
+    Failed { code: Option<i32>, stderr: String },
+    /// The source command succeeded without producing any entry.
+    Empty { stderr: String },
+}
+
+/// The exit code of shells for commands that can't be found.
+const COMMAND_NOT_FOUND_EXIT_CODE: i32 = 127;
+
+/// The exit code of e.g. `grep` and `rg` when nothing matches.
+const NO_MATCH_EXIT_CODE: i32 = 1;
+
+impl SourceError {
+    /// What the source command printed to its stderr, if anything.
+    pub fn stderr(&self) -> &str {
+        match self {
+            Self::Spawn(_) => EMPTY_STRING,
+            Self::Failed { stderr, .. } | Self::Empty { stderr } => stderr,
+        }
+    }
+}
+
+impl Display for SourceError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Spawn(e) => write!(f, "failed to run source command: {e}"),
+            Self::Failed {
+                code: Some(COMMAND_NOT_FOUND_EXIT_CODE),
+                ..
+            } => write!(
+                f,
+                "source command not found (exit code {COMMAND_NOT_FOUND_EXIT_CODE})"
+            ),
+            Self::Failed {
+                code: Some(code), ..
+            } => write!(f, "source command failed (exit code {code})"),
+            Self::Failed { code: None, .. } => {
+                write!(f, "source command was killed by a signal")
+            }
+            Self::Empty { .. } => {
+                write!(f, "source command produced no entries")
+            }
+        }
+    }
+}
+
+/// How long to wait for the query to settle before re-running a dynamic
+/// source command, unless configured otherwise (see `with_query_debounce`).
+const DEFAULT_QUERY_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Everything needed to (re)run a channel's source command.
+#[derive(Debug, Clone)]
+struct Source {
+    command: String,
+    interactive: bool,
+    delimiter: u8,
+    /// What to write to the source command's stdin, if anything.
+    stdin: Option<Vec<u8>>,
+    /// The fields of each line that are matched against.
+    fields: Fields,
+    /// Whether lines contain ANSI escape codes (see `ChannelPrototype::ansi`).
+    ansi: bool,
+    /// Whether the command was formatted with the user's query (see
+    /// `with_query`).
+    queried: bool,
+}
+
+impl Source {
+    /// The source for the given prototype, fed with the given entries.
+    ///
+    /// If the source command contains the `{+}` placeholder, it is replaced
+    /// by the (quoted) entries. Otherwise, the entries are written to the
+    /// command's stdin, one per line.
+    fn new(prototype: &ChannelPrototype, input: &[Entry]) -> Self {
+        let delimiter = if prototype.read0 { b'\0' } else { b'\n' };
+        let mut command = prototype.source_command.clone();
+        let mut stdin = None;
+        if command.contains(SELECTED_ENTRIES_PLACEHOLDER) {
+            command = format_entries(
+                &command,
+                &input.iter().map(|e| &e.name).collect::<Vec<_>>(),
+            );
+        } else if !input.is_empty() {
+            let mut bytes = Vec::new();
+            for entry in input {
+                match &entry.raw {
+                    Some(raw) => bytes.extend_from_slice(raw),
+                    None => bytes.extend_from_slice(entry.name.as_bytes()),
+                }
+                bytes.push(delimiter);
+            }
+            stdin = Some(bytes);
+        }
+        Self {
+            command,
+            interactive: prototype.interactive,
+            delimiter,
+            stdin,
+            fields: Fields::new(prototype),
+            ansi: prototype.ansi,
+            queried: false,
+        }
+    }
+
+    /// Whether the source command depends on the user's query.
+    fn is_dynamic(&self) -> bool {
+        self.command.contains(QUERY_PLACEHOLDER)
+    }
+
+    /// The source to run for the given query, if any.
+    ///
+    /// Dynamic sources aren't run while the query is empty, as they would
+    /// most likely either fail or list everything there is.
+    fn with_query(&self, query: &str) -> Option<Self> {
+        let mut source = self.clone();
+        if self.is_dynamic() {
+            if query.is_empty() {
+                return None;
+            }
+            source.command = format_query(&self.command, query);
+            source.queried = true;
+        }
+        Some(source)
+    }
 }
 
 impl Default for Channel {
@@ -35,45 +215,272 @@ impl Default for Channel {
 
 impl Channel {
     pub fn new(prototype: &ChannelPrototype) -> Self {
-        let matcher = Matcher::new(Config::default());
-        let injector = matcher.injector();
-        let crawl_handle = tokio::spawn(load_candidates(
-            prototype.source_command.to_string(),
-            prototype.interactive,
-            injector,
-        ));
+        Self::with_input(prototype, &[])
+    }
+
+    /// Create a channel whose source command is fed with the given entries,
+    /// e.g. entries sent over from another channel.
+    ///
+    /// See `Source::new` for how the entries are passed to the command.
+    pub fn with_input(prototype: &ChannelPrototype, input: &[Entry]) -> Self {
+        Self::with_matcher_config(prototype, input, Config::default())
+    }
+
+    /// Create a channel fed with the given entries (see `with_input`) whose
+    /// matcher uses the given configuration, as overridden by the
+    /// prototype.
+    pub fn with_matcher_config(
+        prototype: &ChannelPrototype,
+        input: &[Entry],
+        matcher_config: Config,
+    ) -> Self {
+        let matcher = Matcher::with_columns(
+            prototype.matcher_config(matcher_config),
+            prototype.columns.clone().unwrap_or_default(),
+        );
+        let processes = ProcessTracker::default();
+        let source = Source::new(prototype, input);
+        let source_error = Arc::new(Mutex::new(None));
+        let crawl_handle = source.with_query(EMPTY_STRING).map(|source| {
+            tokio::spawn(load_candidates(
+                source,
+                matcher.injector(),
+                processes.clone(),
+                source_error.clone(),
+            ))
+        });
         Self {
             matcher,
             preview_command: prototype.preview_command.clone(),
             name: prototype.name.to_string(),
             selected_entries: HashSet::with_hasher(FxBuildHasher),
             crawl_handle,
+            processes,
+            source,
+            source_error,
+            // invalid intervals are reported when loading the cable
+            watch: prototype.watch_interval().ok().flatten(),
+            loaded_at: Instant::now(),
+            query: EMPTY_STRING.to_string(),
+            query_changed_at: None,
+            query_debounce: DEFAULT_QUERY_DEBOUNCE,
+            actions: prototype.resolved_actions(),
+            interrupted: false,
+            frecency_mode: prototype.frecency,
+            frecency: None,
+        }
+    }
+
+    /// Wait for the query to stay untouched for the given duration before
+    /// re-running a dynamic source command with it.
+    pub fn with_query_debounce(mut self, query_debounce: Duration) -> Self {
+        self.query_debounce = query_debounce;
+        self
+    }
+
+    /// Rank the channel's entries using the given frecency records, provided
+    /// the channel was configured to do so.
+    pub fn with_frecency(mut self, frecency: Frecency) -> Self {
+        if self.frecency_mode.is_some() {
+            self.frecency = Some(frecency);
+            self.update_promotion();
+        }
+        self
+    }
+
+    /// Record that the given entries were picked, for channels ranking their
+    /// entries by frecency.
+    pub fn record_selection<'a>(
+        &mut self,
+        entries: impl IntoIterator<Item = &'a Entry>,
+    ) -> Result<()> {
+        let Some(frecency) = &mut self.frecency else {
+            return Ok(());
+        };
+        let names = entries
+            .into_iter()
+            // entries that aren't valid UTF-8 can't be told apart reliably
+            .filter(|e| e.raw.is_none())
+            .map(|e| e.name.as_str())
+            .collect::<Vec<_>>();
+        frecency.record(&names)?;
+        self.update_promotion();
+        Ok(())
+    }
+
+    /// Forget the frecency of the given entry.
+    pub fn forget(&mut self, entry: &Entry) -> Result<()> {
+        let Some(frecency) = &mut self.frecency else {
+            return Ok(());
+        };
+        frecency.forget(&entry.name)?;
+        self.update_promotion();
+        Ok(())
+    }
+
+    fn update_promotion(&mut self) {
+        let (Some(frecency), Some(mode)) =
+            (&self.frecency, self.frecency_mode)
+        else {
+            return;
+        };
+        if frecency.is_empty() {
+            self.matcher.set_promotion(None);
+            return;
+        }
+        let scores = frecency.scores();
+        let ansi = self.source.ansi;
+        self.matcher.set_promotion(Some((
+            Arc::new(move |item: &Vec<u8>| {
+                if ansi {
+                    // entries are recorded without their escape codes
+                    let name = strip_ansi(&String::from_utf8_lossy(item));
+                    scores.get(name.as_bytes()).copied()
+                } else {
+                    scores.get(item).copied()
+                }
+            }),
+            mode,
+        )));
+    }
+
+    /// Clear the channel's entries and run its source command again.
+    ///
+    /// The current pattern and selected entries are kept as is. If the
+    /// source command is still running, it is killed beforehand.
+    pub fn reload(&mut self) {
+        debug!("Reloading channel {:?}", self.name);
+        if let Some(crawl_handle) = &self.crawl_handle {
+            crawl_handle.abort();
+        }
+        self.processes.kill_all();
+        self.matcher.restart();
+        // a task that was just aborted may still hold on to the previous one
+        self.source_error = Arc::new(Mutex::new(None));
+        self.crawl_handle =
+            self.source.with_query(&self.query).map(|source| {
+                tokio::spawn(load_candidates(
+                    source,
+                    self.matcher.injector(),
+                    self.processes.clone(),
+                    self.source_error.clone(),
+                ))
+            });
+        self.loaded_at = Instant::now();
+        self.query_changed_at = None;
+    }
+
+    /// Stop the channel's source command if it is still running, e.g. when
+    /// the channel is put in the background.
+    ///
+    /// The entries loaded so far are kept, and the source is re-run from
+    /// scratch once the channel is resumed.
+    pub fn pause(&mut self) {
+        if let Some(crawl_handle) =
+            self.crawl_handle.as_ref().filter(|h| !h.is_finished())
+        {
+            debug!("Pausing channel {:?}", self.name);
+            crawl_handle.abort();
+            self.processes.kill_all();
+            self.interrupted = true;
         }
     }
 
+    /// Bring back a paused channel, re-running its source if it was
+    /// interrupted.
+    pub fn resume(&mut self) {
+        if std::mem::take(&mut self.interrupted) {
+            self.reload();
+        }
+    }
+
+    /// Whether the channel's source command depends on the user's query,
+    /// i.e. whether it contains the `{q}` placeholder.
+    pub fn is_dynamic(&self) -> bool {
+        self.source.is_dynamic()
+    }
+
+    /// Set the query to run a dynamic source command with.
+    ///
+    /// The source command isn't re-run right away but only once the query
+    /// has settled (see `should_run_query`).
+    pub fn set_query(&mut self, query: &str) {
+        if self.is_dynamic() && query != self.query {
+            self.query = query.to_string();
+            self.query_changed_at = Some(Instant::now());
+        }
+    }
+
+    /// Whether the query changed and has been left untouched for long enough
+    /// that the source command should be re-run with it.
+    pub fn should_run_query(&self) -> bool {
+        self.query_changed_at.is_some_and(|changed_at| {
+            changed_at.elapsed() >= self.query_debounce
+        })
+    }
+
+    /// Whether the channel's `watch` interval has elapsed since its source
+    /// was last (re)loaded, provided it is done loading.
+    pub fn should_reload(&self) -> bool {
+        self.watch.is_some_and(|watch| {
+            !self.is_loading() && self.loaded_at.elapsed() >= watch
+        })
+    }
+
     pub fn find(&mut self, pattern: &str) {
         self.matcher.find(pattern);
     }
 
+    /// Filter the channel's entries with the given regex instead of fuzzy
+    /// matching them (see `Matcher::find_regex`).
+    pub fn find_regex(&mut self, pattern: &str) -> Result<(), regex::Error> {
+        self.matcher.find_regex(pattern)
+    }
+
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool) {
+        self.matcher.set_case_sensitive(case_sensitive);
+    }
+
     pub fn results(&mut self, num_entries: u32, offset: u32) -> Vec<Entry> {
         self.matcher.tick();
         self.matcher
             .results(num_entries, offset)
             .into_iter()
             .map(|item| {
-                let path = item.matched_string;
-                Entry::new(path).with_name_match_indices(&item.match_indices)
+                let (entry, styles) = self.entry(item.inner);
+                let fields = &self.source.fields;
+                let (display, indices) = fields.display(
+                    &entry.name,
+                    &item.match_indices,
+                    &item.column_indices,
+                );
+                let styles = fields.display_styles(&entry.name, &styles);
+                let output = fields.output(&entry.name);
+                let entry = entry
+                    .with_display(display)
+                    .with_output(output)
+                    .with_name_match_indices(&indices);
+                if styles.is_empty() {
+                    entry
+                } else {
+                    entry.with_name_styles(styles)
+                }
             })
             .collect()
     }
 
     pub fn get_result(&self, index: u32) -> Option<Entry> {
         self.matcher.get_result(index).map(|item| {
-            let name = item.matched_string;
+            let (entry, _) = self.entry(item.inner);
+            let output = self.source.fields.output(&entry.name);
+            let entry = entry.with_output(output);
             if let Some(cmd) = &self.preview_command {
                 if let Some(offset_expr) = &cmd.offset_expr {
-                    let offset_string =
-                        format_string(offset_expr, &name, &cmd.delimiter);
+                    let offset_string = format_string(
+                        offset_expr,
+                        &entry.name,
+                        &cmd.delimiter,
+                    );
                     let offset_str = {
                         offset_string
                             .strip_prefix('\'')
@@ -81,7 +488,7 @@ impl Channel {
                             .unwrap_or(&offset_string)
                     };
 
-                    return Entry::new(name).with_line_number(
+                    return entry.with_line_number(
                         offset_str.parse::<usize>().unwrap_or_else(|_| {
                             panic!(
                                 "Failed to parse line number from {}",
@@ -91,10 +498,24 @@ impl Channel {
                     );
                 }
             }
-            Entry::new(name)
+            entry
         })
     }
 
+    /// The entry for the given line produced by the source, along with its
+    /// styles if the source produces ANSI escape codes.
+    ///
+    /// Such entries are made of the line stripped of its escape codes, and
+    /// of its lossy representation if it isn't valid UTF-8.
+    fn entry(&self, line: Vec<u8>) -> (Entry, Vec<StyledRange>) {
+        if self.source.ansi {
+            let (name, styles) = parse_ansi(&String::from_utf8_lossy(&line));
+            (Entry::new(name), styles)
+        } else {
+            (Entry::from_bytes(line), Vec::new())
+        }
+    }
+
     pub fn selected_entries(&self) -> &FxHashSet<Entry> {
         &self.selected_entries
     }
@@ -116,58 +537,1068 @@ impl Channel {
     }
 
     pub fn running(&self) -> bool {
-        self.matcher.status.running || !self.crawl_handle.is_finished()
+        self.matcher.status.running || self.is_loading()
     }
 
-    pub fn shutdown(&self) {}
+    /// Whether the source command is still being run.
+    fn is_loading(&self) -> bool {
+        self.crawl_handle.as_ref().is_some_and(|h| !h.is_finished())
+    }
+
+    /// How the source command failed the last time it was run, if it did.
+    ///
+    /// This is only known once the source command has exited.
+    pub fn source_error(&self) -> Option<SourceError> {
+        self.source_error.lock().clone()
+    }
+
+    /// Kill the channel's source command along with any process it spawned.
+    pub fn shutdown(&self) {
+        debug!("Shutting down channel {:?}", self.name);
+        self.processes.shutdown();
+    }
 
     pub fn supports_preview(&self) -> bool {
         self.preview_command.is_some()
     }
+
+    /// The channel's custom actions, sorted by name.
+    pub fn actions(&self) -> &BTreeMap<String, ActionSpec> {
+        &self.actions
+    }
+
+    /// The name of the custom action bound to the given key, if any.
+    pub fn action_for_key(&self, key: &Key) -> Option<&str> {
+        self.actions
+            .iter()
+            .find(|(_, action)| action.key == *key)
+            .map(|(name, _)| name.as_str())
+    }
 }
 
-#[allow(clippy::unused_async)]
+impl Drop for Channel {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// The maximum number of lines pushed into the matcher at once.
+const MAX_BATCH_SIZE: usize = 1024;
+/// The maximum number of lines pushed into the matcher that it hasn't taken
+/// in yet, past which the source's output stops being read.
+///
+/// The matcher's injector is unbounded, so this is what keeps a source that
+/// produces entries faster than they can be matched from piling them up in
+/// memory: it gets blocked on its output instead.
+const MAX_PENDING_ITEMS: u32 = 100_000;
+/// How often to check whether the matcher caught up, once it's behind.
+const PENDING_ITEMS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Run the source command and push its output into the matcher.
+///
+/// If the source command can't be run, fails or doesn't produce any entry,
+/// the reason is stored in `source_error`.
 async fn load_candidates(
-    command: String,
-    interactive: bool,
-    injector: Injector<String>,
+    source: Source,
+    injector: Injector<Vec<u8>>,
+    processes: ProcessTracker,
+    source_error: Arc<Mutex<Option<SourceError>>>,
 ) {
-    debug!("Loading candidates from command: {:?}", command);
-    let mut child = shell_command(interactive)
-        .arg(command)
+    debug!("Loading candidates from command: {:?}", source.command);
+    let mut command = Command::from(shell_command(source.interactive));
+    command
+        .arg(source.command)
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("failed to execute process");
-
-    if let Some(out) = child.stdout.take() {
-        let reader = BufReader::new(out);
-        let mut produced_output = false;
-
-        #[allow(clippy::manual_flatten)]
-        for line in reader.lines() {
-            if let Ok(l) = line {
-                if !l.trim().is_empty() {
-                    let () = injector.push(l, |e, cols| {
-                        // PERF: maybe we can avoid cloning here by using &Utf32Str
-                        cols[0] = e.clone().into();
-                    });
-                    produced_output = true;
-                }
+        .stderr(Stdio::piped());
+    if source.stdin.is_some() {
+        command.stdin(Stdio::piped());
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            debug!("Failed to run source command: {:?}", e);
+            *source_error.lock() = Some(SourceError::Spawn(e.to_string()));
+            return;
+        }
+    };
+    let pid = child.id();
+    if let Some(pid) = pid {
+        processes.register(pid);
+    }
+
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+
+    // stdin is written and stderr is read concurrently so that neither pipe
+    // can fill up and block the source while we're reading its stdout
+    let ((), produced_output, errors) = tokio::join!(
+        write_stdin(stdin, source.stdin.unwrap_or_default()),
+        read_entries(
+            stdout,
+            source.delimiter,
+            &injector,
+            &source.fields,
+            source.ansi
+        ),
+        read_stderr(stderr),
+    );
+
+    let exit_status = child.wait().await;
+    if let Some(pid) = pid {
+        processes.unregister(pid);
+    }
+
+    let stderr = String::from_utf8_lossy(&errors).trim_end().to_string();
+    let error = match exit_status {
+        Ok(status) if status.success() => {
+            (!produced_output).then_some(SourceError::Empty { stderr })
+        }
+        // the query not matching anything isn't worth reporting
+        Ok(status)
+            if source.queried
+                && status.code() == Some(NO_MATCH_EXIT_CODE)
+                && stderr.is_empty() =>
+        {
+            None
+        }
+        Ok(status) => Some(SourceError::Failed {
+            code: status.code(),
+            stderr,
+        }),
+        Err(e) => Some(SourceError::Spawn(e.to_string())),
+    };
+    if let Some(error) = &error {
+        debug!("Source command failed: {:?}", error);
+    }
+    *source_error.lock() = error;
+}
+
+/// Read lines from the source's output and push them into the matcher in
+/// batches.
+///
+/// Lines are separated by `delimiter`, which is either a newline or a NUL
+/// character. In the latter case, entries are kept as is (apart from the
+/// delimiter itself) since they may legitimately contain any other character.
+///
+/// A batch is pushed as soon as it's full or when everything the source has
+/// produced so far was consumed, so that slow sources still get their
+/// entries displayed right away. Reading is paused while the matcher is too
+/// far behind (see `MAX_PENDING_ITEMS`).
+///
+/// Returns whether any line was pushed at all.
+async fn read_entries<R>(
+    source: R,
+    delimiter: u8,
+    injector: &Injector<Vec<u8>>,
+    fields: &Fields,
+    ansi: bool,
+) -> bool
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(source);
+    let mut batch = Vec::with_capacity(MAX_BATCH_SIZE);
+    let mut buf = Vec::new();
+    let mut produced_output = false;
+    loop {
+        buf.clear();
+        match reader.read_until(delimiter, &mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let line = buf.strip_suffix(&[delimiter]).unwrap_or(&buf);
+        if delimiter == b'\n' {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if !line.trim_ascii().is_empty() {
+                batch.push(line.to_vec());
             }
+        } else if !line.is_empty() {
+            batch.push(line.to_vec());
+        }
+        if batch.len() >= MAX_BATCH_SIZE
+            || (!batch.is_empty() && reader.buffer().is_empty())
+        {
+            produced_output = true;
+            inject_batch(&mut batch, injector, fields, ansi).await;
         }
+    }
+    if !batch.is_empty() {
+        produced_output = true;
+        inject_batch(&mut batch, injector, fields, ansi).await;
+    }
+    produced_output
+}
 
-        if !produced_output {
-            let reader = BufReader::new(child.stderr.take().unwrap());
-            for line in reader.lines() {
-                let line = line.unwrap();
-                if !line.trim().is_empty() {
-                    let () = injector.push(line, |e, cols| {
-                        cols[0] = e.clone().into();
-                    });
-                }
+/// Push a batch of lines into the matcher, then wait for it to catch up if
+/// it's too far behind.
+async fn inject_batch(
+    batch: &mut Vec<Vec<u8>>,
+    injector: &Injector<Vec<u8>>,
+    fields: &Fields,
+    ansi: bool,
+) {
+    for line in batch.drain(..) {
+        push_line(injector, line, fields, ansi);
+    }
+    while injector.pending_count() > MAX_PENDING_ITEMS {
+        tokio::time::sleep(PENDING_ITEMS_POLL_INTERVAL).await;
+    }
+    // give other tasks (rendering, previews, etc.) a chance to run
+    tokio::task::yield_now().await;
+}
+
+/// Push a single line into the matcher.
+///
+/// Lines that aren't valid UTF-8 are matched against using their lossy
+/// representation, stripped of ANSI escape codes if `ansi` is set,
+/// restricted to the channel's `match_fields` if any, and split into the
+/// channel's named columns.
+fn push_line(
+    injector: &Injector<Vec<u8>>,
+    line: Vec<u8>,
+    fields: &Fields,
+    ansi: bool,
+) {
+    let () = injector.push(line, |e, cols| {
+        let mut line = String::from_utf8_lossy(e);
+        if ansi {
+            line = strip_ansi(&line).into();
+        }
+        cols[0] = fields.matched(&line).as_ref().into();
+        for (col, column) in cols[1..].iter_mut().zip(fields.columns(&line)) {
+            *col = column.into();
+        }
+    });
+}
+
+/// Write the given input to the source's stdin, closing it afterwards.
+async fn write_stdin(stdin: Option<ChildStdin>, input: Vec<u8>) {
+    if let Some(mut stdin) = stdin {
+        // the source may exit without reading all of its input
+        let _ = stdin.write_all(&input).await;
+    }
+}
+
+/// The maximum number of bytes of the source's error output that are kept,
+/// as it only ends up in the results pane and notifications.
+const MAX_STDERR_SIZE: u64 = 64 * 1024;
+
+/// Read the source's error output, keeping at most `MAX_STDERR_SIZE` bytes
+/// of it.
+async fn read_stderr<R>(mut source: R) -> Vec<u8>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut buf = Vec::new();
+    let _ = (&mut source)
+        .take(MAX_STDERR_SIZE)
+        .read_to_end(&mut buf)
+        .await;
+    // the rest is still read so that the source doesn't block on a full pipe
+    let _ = tokio::io::copy(&mut source, &mut tokio::io::sink()).await;
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::config::TieBreak;
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    /// Tick the channel until it reaches the given number of entries.
+    async fn wait_for_entries(channel: &mut Channel, count: u32) {
+        while channel.total_count() < count {
+            channel.results(1, 0);
+            sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_read_stderr_is_capped() {
+        let max = usize::try_from(MAX_STDERR_SIZE).unwrap();
+        let stderr = vec![b'x'; 2 * max];
+        let read = read_stderr(stderr.as_slice()).await;
+        assert_eq!(read.len(), max);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_load_candidates_does_not_block_runtime() {
+        // a source that keeps running after having produced its output
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "seq 1 1000; sleep 30",
+            false,
+            None,
+        ));
+
+        // on a single threaded runtime, this can only make progress if
+        // ingestion yields back to the scheduler
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 1000))
+            .await
+            .expect("source ingestion starved the runtime");
+        assert!(channel.running());
+
+        channel.shutdown();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_load_candidates_large_source() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "seq 1 100000",
+            false,
+            None,
+        ));
+
+        timeout(
+            Duration::from_secs(5),
+            wait_for_entries(&mut channel, 100_000),
+        )
+        .await
+        .expect("not all entries were loaded");
+        assert_eq!(channel.total_count(), 100_000);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_load_candidates_waits_for_the_matcher() {
+        let count = 3 * MAX_PENDING_ITEMS;
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            &format!("seq 1 {count}"),
+            false,
+            None,
+        ));
+
+        // the matcher only takes entries in when ticked
+        sleep(Duration::from_millis(500)).await;
+        let pending = channel.matcher.injector().pending_count();
+        assert!(pending > 0);
+        assert!(
+            pending
+                <= MAX_PENDING_ITEMS + u32::try_from(MAX_BATCH_SIZE).unwrap()
+        );
+        assert!(channel.is_loading());
+
+        timeout(
+            Duration::from_secs(5),
+            wait_for_entries(&mut channel, count),
+        )
+        .await
+        .expect("not all entries were loaded");
+        assert_eq!(channel.total_count(), count);
+    }
+
+    /// Tick the channel until its source command is done loading.
+    async fn wait_for_source(channel: &mut Channel) {
+        loop {
+            channel.results(1, 0);
+            if !channel.running() {
+                break;
+            }
+            sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_load_candidates_reports_stderr() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "echo 'something went wrong' >&2",
+            false,
+            None,
+        ));
+
+        timeout(Duration::from_secs(2), wait_for_source(&mut channel))
+            .await
+            .expect("source did not finish");
+        // stderr isn't loaded as entries
+        assert_eq!(channel.total_count(), 0);
+        assert_eq!(
+            channel.source_error(),
+            Some(SourceError::Empty {
+                stderr: String::from("something went wrong")
+            })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_load_candidates_reports_exit_code() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "echo a; echo 'oops' >&2; exit 3",
+            false,
+            None,
+        ));
+
+        timeout(Duration::from_secs(2), wait_for_source(&mut channel))
+            .await
+            .expect("source did not finish");
+        // entries produced before the failure are kept
+        assert_eq!(channel.total_count(), 1);
+        let error = channel.source_error().unwrap();
+        assert_eq!(
+            error,
+            SourceError::Failed {
+                code: Some(3),
+                stderr: String::from("oops")
+            }
+        );
+        assert_eq!(error.to_string(), "source command failed (exit code 3)");
+
+        channel.reload();
+        assert_eq!(channel.source_error(), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dynamic_source_without_match() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "grep -- {q} /dev/null",
+            false,
+            None,
+        ));
+        channel.set_query("foo");
+        channel.reload();
+
+        timeout(Duration::from_secs(2), wait_for_source(&mut channel))
+            .await
+            .expect("source did not finish");
+        assert_eq!(channel.total_count(), 0);
+        assert_eq!(channel.source_error(), None);
+
+        // errors are still reported
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "echo oops {q} >&2; exit 1",
+            false,
+            None,
+        ));
+        channel.set_query("foo");
+        channel.reload();
+        timeout(Duration::from_secs(2), wait_for_source(&mut channel))
+            .await
+            .expect("source did not finish");
+        assert!(matches!(
+            channel.source_error(),
+            Some(SourceError::Failed {
+                code: Some(NO_MATCH_EXIT_CODE),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_load_candidates_reports_missing_command() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "this-command-does-not-exist",
+            false,
+            None,
+        ));
+
+        timeout(Duration::from_secs(2), wait_for_source(&mut channel))
+            .await
+            .expect("source did not finish");
+        assert_eq!(channel.total_count(), 0);
+        let error = channel.source_error().unwrap();
+        assert!(matches!(
+            error,
+            SourceError::Failed {
+                code: Some(COMMAND_NOT_FOUND_EXIT_CODE),
+                ..
             }
+        ));
+        assert!(error.stderr().contains("this-command-does-not-exist"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_load_candidates_without_error() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test", "echo a", false, None,
+        ));
+
+        timeout(Duration::from_secs(2), wait_for_source(&mut channel))
+            .await
+            .expect("source did not finish");
+        assert_eq!(channel.total_count(), 1);
+        assert_eq!(channel.source_error(), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_reload_reruns_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("entries");
+        std::fs::write(&file, "a\nb\n").unwrap();
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            &format!("cat '{}'", file.display()),
+            false,
+            None,
+        ));
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+
+        std::fs::write(&file, "a\nb\nc\n").unwrap();
+        channel.reload();
+
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 3))
+            .await
+            .expect("source was not reloaded");
+        // give the source a chance to (wrongly) produce duplicates
+        sleep(Duration::from_millis(50)).await;
+        channel.results(10, 0);
+        assert_eq!(channel.total_count(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_resume_reruns_interrupted_source() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "echo a; sleep 30",
+            false,
+            None,
+        ));
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 1))
+            .await
+            .expect("not all entries were loaded");
+
+        channel.pause();
+        assert!(channel.processes.is_empty());
+        channel.resume();
+        assert!(channel.is_loading());
+
+        // a source that is done loading is left as is
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test", "echo a", false, None,
+        ));
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 1))
+            .await
+            .expect("not all entries were loaded");
+        while channel.is_loading() {
+            sleep(Duration::from_millis(5)).await;
+        }
+        channel.pause();
+        channel.resume();
+        assert!(!channel.is_loading());
+        assert_eq!(channel.total_count(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_should_reload_after_watch_interval() {
+        let mut channel = Channel::new(
+            &ChannelPrototype::new("test", "echo a", false, None)
+                .with_watch(Some(0.05)),
+        );
+        assert!(!channel.should_reload());
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 1))
+            .await
+            .expect("not all entries were loaded");
+
+        sleep(Duration::from_millis(100)).await;
+        assert!(channel.should_reload());
+        channel.reload();
+        assert!(!channel.should_reload());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dynamic_source_runs_with_query() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "echo \"query: \"{q}",
+            false,
+            None,
+        ));
+        assert!(channel.is_dynamic());
+
+        channel.set_query("it's");
+        channel.set_query("it's me");
+        assert!(!channel.should_run_query());
+        sleep(DEFAULT_QUERY_DEBOUNCE).await;
+        assert!(channel.should_run_query());
+        channel.reload();
+        assert!(!channel.should_run_query());
+
+        timeout(Duration::from_secs(2), async {
+            while channel.get_result(0).map(|e| e.name)
+                != Some("query: it's me".to_string())
+            {
+                channel.results(1, 0);
+                sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("source was not re-run with the query");
+        assert_eq!(channel.total_count(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dynamic_source_not_run_with_empty_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("runs");
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            &format!("echo {{q}} >> '{}'; echo {{q}}", log.display()),
+            false,
+            None,
+        ))
+        .with_query_debounce(Duration::ZERO);
+        assert!(!channel.running());
+        assert!(channel.processes.is_empty());
+
+        channel.set_query("foo");
+        channel.reload();
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 1))
+            .await
+            .expect("source was not run");
+
+        // clearing the query clears the entries without re-running the source
+        channel.set_query("");
+        channel.reload();
+        assert!(!channel.running());
+        sleep(Duration::from_millis(50)).await;
+        channel.results(10, 0);
+        assert_eq!(channel.total_count(), 0);
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "foo\n");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_query_debounce() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test", "echo {q}", false, None,
+        ))
+        .with_query_debounce(Duration::ZERO);
+        channel.set_query("foo");
+        assert!(channel.should_run_query());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_set_query_on_static_source() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test", "echo {}", false, None,
+        ));
+        assert!(!channel.is_dynamic());
+        channel.set_query("foo");
+        sleep(DEFAULT_QUERY_DEBOUNCE).await;
+        assert!(!channel.should_run_query());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_load_candidates_read0() {
+        let mut channel = Channel::new(
+            &ChannelPrototype::new(
+                "test",
+                "printf 'multi\\nline\\0single\\0'",
+                false,
+                None,
+            )
+            .with_read0(true),
+        );
+
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+        let mut names = (0..2)
+            .map(|i| channel.get_result(i).unwrap().name)
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["multi\nline", "single"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_with_input_substitutes_entries() {
+        let input =
+            [Entry::new("a b".to_string()), Entry::new("c".to_string())];
+        let mut channel = Channel::with_input(
+            &ChannelPrototype::new("test", "printf '%s\\n' {+}", false, None),
+            &input,
+        );
+
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+        let mut names = (0..2)
+            .map(|i| channel.get_result(i).unwrap().name)
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["a b", "c"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_with_input_pipes_entries_to_stdin() {
+        let input = [
+            Entry::new("foo".to_string()),
+            Entry::from_bytes(b"caf\xe9".to_vec()),
+        ];
+        let mut channel = Channel::with_input(
+            &ChannelPrototype::new("test", "sed 's/^/> /'", false, None),
+            &input,
+        );
+
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+        let mut entries = (0..2)
+            .map(|i| channel.get_result(i).unwrap().stdout_bytes())
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(entries, vec![b"> caf\xe9".to_vec(), b"> foo".to_vec()]);
+    }
+
+    fn settled_results(channel: &mut Channel) -> Vec<Entry> {
+        let mut results = channel.results(10, 0);
+        while channel.matcher.status.running {
+            results = channel.results(10, 0);
+        }
+        results
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_find_regex() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "printf 'main.rs\\nlib.rs\\nmain.txt\\n'",
+            false,
+            None,
+        ));
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 3))
+            .await
+            .expect("not all entries were loaded");
+
+        channel.find_regex(r"^(\w+)\.rs$").unwrap();
+        let results = settled_results(&mut channel);
+        let names =
+            results.iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["main.rs", "lib.rs"]);
+        assert_eq!(results[1].name_match_ranges, Some(vec![(0, 3)]));
+
+        // an invalid regex leaves the current filter as is
+        assert!(channel.find_regex("main(").is_err());
+        assert_eq!(settled_results(&mut channel).len(), 2);
+
+        channel.find("txt");
+        let results = settled_results(&mut channel);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "main.txt");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_case_sensitive_matching() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "printf 'Foo\\nfoo\\n'",
+            false,
+            None,
+        ));
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+
+        channel.find("foo");
+        assert_eq!(settled_results(&mut channel).len(), 2);
+        channel.set_case_sensitive(true);
+        let results = settled_results(&mut channel);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "foo");
+    }
+
+    async fn find_names(
+        prototype: &ChannelPrototype,
+        count: u32,
+    ) -> Vec<String> {
+        let mut channel = Channel::new(prototype);
+        timeout(
+            Duration::from_secs(2),
+            wait_for_entries(&mut channel, count),
+        )
+        .await
+        .expect("not all entries were loaded");
+        channel.find("file");
+        settled_results(&mut channel)
+            .into_iter()
+            .map(|e| e.name)
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_no_sort_keeps_source_order() {
+        let prototype = ChannelPrototype::new(
+            "test",
+            "printf 'f_i_l_e\\nfile\\nother\\n'",
+            false,
+            None,
+        );
+        assert_eq!(find_names(&prototype, 3).await, vec!["file", "f_i_l_e"]);
+        assert_eq!(
+            find_names(&prototype.with_sort(Some(false)), 3).await,
+            vec!["f_i_l_e", "file"]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_no_sort_past_the_first_results() {
+        let prototype =
+            ChannelPrototype::new("test", "seq 1 5000", false, None)
+                .with_sort(Some(false));
+        let mut channel = Channel::new(&prototype);
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 5000))
+            .await
+            .expect("not all entries were loaded");
+        channel.find("1");
+        settled_results(&mut channel);
+        let expected = (1..=5000)
+            .map(|n: u32| n.to_string())
+            .filter(|n| n.contains('1'))
+            .collect::<Vec<_>>();
+
+        // further than the results that were looked at so far
+        assert_eq!(
+            channel.get_result(1500).map(|e| e.name).as_ref(),
+            expected.get(1500)
+        );
+        let names = channel
+            .results(10, 1000)
+            .into_iter()
+            .map(|e| e.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, expected[1000..1010]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_tiebreak() {
+        let mut prototype = ChannelPrototype::new(
+            "test",
+            "printf 'abcd/file\\na/b/file\\n'",
+            false,
+            None,
+        );
+        assert_eq!(
+            find_names(&prototype, 2).await,
+            vec!["a/b/file", "abcd/file"]
+        );
+        prototype.tiebreak = Some(vec![TieBreak::PathDepth]);
+        assert_eq!(
+            find_names(&prototype, 2).await,
+            vec!["abcd/file", "a/b/file"]
+        );
+        prototype.tiebreak = Some(vec![TieBreak::Index]);
+        assert_eq!(
+            find_names(&prototype, 2).await,
+            vec!["abcd/file", "a/b/file"]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_tiebreak_after_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("entries");
+        std::fs::write(&file, "abcd/file\na/b/file\n").unwrap();
+        let mut prototype = ChannelPrototype::new(
+            "test",
+            &format!("cat '{}'", file.display()),
+            false,
+            None,
+        );
+        prototype.tiebreak = Some(vec![TieBreak::Index]);
+        let mut channel = Channel::new(&prototype);
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+        channel.find("file");
+        let names = |results: Vec<Entry>| {
+            results.into_iter().map(|e| e.name).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(settled_results(&mut channel)),
+            vec!["abcd/file", "a/b/file"]
+        );
+
+        // same number of items and matches, and the same pattern
+        std::fs::write(&file, "a/b/file\nabcd/file\n").unwrap();
+        channel.reload();
+        timeout(Duration::from_secs(2), wait_for_source(&mut channel))
+            .await
+            .expect("source was not reloaded");
+        assert_eq!(
+            names(settled_results(&mut channel)),
+            vec!["a/b/file", "abcd/file"]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_match_and_display_fields() {
+        let mut prototype = ChannelPrototype::new(
+            "test",
+            "printf 'file:a:1\\nb:file:2\\n'",
+            false,
+            Some(PreviewCommand::new("echo {0}", ":", None)),
+        );
+        prototype.match_fields = Some(vec![1]);
+        prototype.display_fields = Some(vec![1, 2]);
+        let mut channel = Channel::new(&prototype);
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+
+        channel.find("file");
+        let results = settled_results(&mut channel);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].display_name(), "file:2");
+        assert_eq!(results[0].name_match_ranges, Some(vec![(0, 4)]));
+        assert_eq!(results[0].stdout_repr(), "b:file:2");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_display_and_output_templates() {
+        let mut prototype = ChannelPrototype::new(
+            "test",
+            "printf '1a2b:nginx:12MB\\n'",
+            false,
+            Some(PreviewCommand::new("echo {0}", ":", None)),
+        );
+        prototype.display = Some("{1} ({2})".to_string());
+        prototype.output = Some("{0}".to_string());
+        let mut channel = Channel::new(&prototype);
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 1))
+            .await
+            .expect("not all entries were loaded");
+
+        channel.find("ngx");
+        let results = settled_results(&mut channel);
+        assert_eq!(results[0].display_name(), "nginx (12MB)");
+        assert_eq!(results[0].name_match_ranges, Some(vec![(0, 2), (4, 5)]));
+        assert_eq!(results[0].stdout_repr(), "1a2b");
+        let selected = channel.get_result(0).unwrap();
+        assert_eq!(selected.stdout_repr(), "1a2b");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_column_scoped_query() {
+        let mut prototype = ChannelPrototype::new(
+            "test",
+            "printf 'src/a.rs:1:todo: x\\ntests/a.rs:2:src\\n'",
+            false,
+            Some(PreviewCommand::new("echo {0}", ":", None)),
+        );
+        prototype.columns =
+            Some(vec!["path".into(), "line".into(), "content".into()]);
+        let mut channel = Channel::new(&prototype);
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+
+        channel.find("src");
+        assert_eq!(settled_results(&mut channel).len(), 2);
+
+        channel.find("path:src");
+        let results = settled_results(&mut channel);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "src/a.rs:1:todo: x");
+        assert_eq!(results[0].name_match_ranges, Some(vec![(0, 3)]));
+
+        channel.find("'a.rs content:src");
+        let results = settled_results(&mut channel);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "tests/a.rs:2:src");
+        assert_eq!(
+            results[0].name_match_ranges,
+            Some(vec![(6, 10), (13, 16)])
+        );
+
+        // the last column gets the rest of the entry
+        channel.find("content:x !line:2");
+        let results = settled_results(&mut channel);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "src/a.rs:1:todo: x");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_frecency_boosts_picked_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut channel = Channel::new(
+            &ChannelPrototype::new(
+                "test",
+                "printf 'a\\nb\\nc\\n'",
+                false,
+                None,
+            )
+            .with_frecency(Some(PromotionMode::Boost)),
+        )
+        .with_frecency(Frecency::load(dir.path(), "test"));
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 3))
+            .await
+            .expect("not all entries were loaded");
+        let names = |channel: &mut Channel| {
+            channel
+                .results(10, 0)
+                .into_iter()
+                .map(|e| e.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&mut channel), vec!["a", "b", "c"]);
+
+        channel
+            .record_selection(&[Entry::new("c".to_string())])
+            .unwrap();
+        assert_eq!(names(&mut channel), vec!["c", "a", "b"]);
+
+        channel.forget(&Entry::new("c".to_string())).unwrap();
+        assert_eq!(names(&mut channel), vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_frecency_breaks_ties_between_equal_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut channel = Channel::new(
+            &ChannelPrototype::new(
+                "test",
+                "printf 'xa\\nxb\\nyx\\nxc\\n'",
+                false,
+                None,
+            )
+            .with_frecency(Some(PromotionMode::TieBreaker)),
+        )
+        .with_frecency(Frecency::load(dir.path(), "test"));
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 4))
+            .await
+            .expect("not all entries were loaded");
+        channel
+            .record_selection(&[
+                Entry::new("xc".to_string()),
+                Entry::new("yx".to_string()),
+            ])
+            .unwrap();
+
+        channel.find("x");
+        let names = settled_results(&mut channel)
+            .into_iter()
+            .map(|e| e.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["xc", "xa", "xb", "yx"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_load_candidates_keeps_non_utf8_lines() {
+        let mut channel = Channel::new(&ChannelPrototype::new(
+            "test",
+            "printf 'caf\\351.txt\\nvalid.txt\\n'",
+            false,
+            None,
+        ));
+
+        timeout(Duration::from_secs(2), wait_for_entries(&mut channel, 2))
+            .await
+            .expect("not all entries were loaded");
+
+        channel.find("caf");
+        let mut results = channel.results(10, 0);
+        while channel.matcher.status.running {
+            results = channel.results(10, 0);
         }
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "caf\u{FFFD}.txt");
+        assert_eq!(results[0].stdout_bytes(), b"caf\xe9.txt");
     }
-    let _ = child.wait();
 }
@@ -2,6 +2,8 @@ use std::hash::{Hash, Hasher};
 
 use devicons::FileIcon;
 
+use crate::utils::ansi::StyledRange;
+
 #[derive(Clone, Debug, Eq)]
 pub struct Entry {
     /// The name of the entry.
@@ -12,10 +14,26 @@ pub struct Entry {
     pub name_match_ranges: Option<Vec<(u32, u32)>>,
     /// The optional ranges for matching characters in the value.
     pub value_match_ranges: Option<Vec<(u32, u32)>>,
+    /// The optional styled ranges of characters in the name (e.g. colors
+    /// from ANSI escape codes), as displayed.
+    pub name_styles: Option<Vec<StyledRange>>,
     /// The optional icon associated with the entry.
     pub icon: Option<FileIcon>,
     /// The optional line number associated with the entry.
     pub line_number: Option<usize>,
+    /// The original bytes of the entry if they aren't valid UTF-8.
+    ///
+    /// In that case, `name` only holds a lossy representation of these bytes
+    /// which is fine for matching and displaying but not for outputting.
+    pub raw: Option<Vec<u8>>,
+    /// What to display instead of the name, if it differs (e.g. when only
+    /// some of the entry's fields are displayed).
+    ///
+    /// When set, `name_match_ranges` apply to it rather than to the name.
+    pub display: Option<String>,
+    /// What to output instead of the name, if it differs (e.g. when the
+    /// channel has an `output` template).
+    pub output: Option<String>,
 }
 
 impl Hash for Entry {
@@ -24,6 +42,9 @@ impl Hash for Entry {
         if let Some(line_number) = self.line_number {
             line_number.hash(state);
         }
+        if let Some(raw) = &self.raw {
+            raw.hash(state);
+        }
     }
 }
 
@@ -32,6 +53,7 @@ impl PartialEq<Entry> for &Entry {
         self.name == other.name
             && (self.line_number.is_none() && other.line_number.is_none()
                 || self.line_number == other.line_number)
+            && self.raw == other.raw
     }
 }
 
@@ -40,6 +62,7 @@ impl PartialEq<Entry> for Entry {
         self.name == other.name
             && (self.line_number.is_none() && other.line_number.is_none()
                 || self.line_number == other.line_number)
+            && self.raw == other.raw
     }
 }
 
@@ -98,8 +121,36 @@ impl Entry {
             value: None,
             name_match_ranges: None,
             value_match_ranges: None,
+            name_styles: None,
             icon: None,
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
+        }
+    }
+
+    /// Create a new entry from the raw bytes produced by a source.
+    ///
+    /// If these bytes aren't valid UTF-8, the entry's name will be a lossy
+    /// representation of them while the original bytes are kept around to be
+    /// written back as is when outputting the entry.
+    ///
+    /// ```
+    /// use television::channels::entry::Entry;
+    ///
+    /// let entry = Entry::from_bytes(b"caf\xe9".to_vec());
+    /// assert_eq!(entry.name, "caf\u{FFFD}");
+    /// assert_eq!(entry.stdout_bytes(), b"caf\xe9");
+    /// ```
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(name) => Self::new(name),
+            Err(e) => {
+                let raw = e.into_bytes();
+                Self::new(String::from_utf8_lossy(&raw).into_owned())
+                    .with_raw(raw)
+            }
         }
     }
 
@@ -118,6 +169,11 @@ impl Entry {
         self
     }
 
+    pub fn with_name_styles(mut self, styles: Vec<StyledRange>) -> Self {
+        self.name_styles = Some(styles);
+        self
+    }
+
     pub fn with_icon(mut self, icon: FileIcon) -> Self {
         self.icon = Some(icon);
         self
@@ -128,10 +184,45 @@ impl Entry {
         self
     }
 
+    pub fn with_raw(mut self, raw: Vec<u8>) -> Self {
+        self.raw = Some(raw);
+        self
+    }
+
+    pub fn with_display(mut self, display: Option<String>) -> Self {
+        self.display = display;
+        self
+    }
+
+    /// What gets displayed for this entry in the results list.
+    pub fn display_name(&self) -> &str {
+        self.display.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn with_output(mut self, output: Option<String>) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// A lossy string representation of what gets written to stdout for this
+    /// entry (see `stdout_bytes`).
     pub fn stdout_repr(&self) -> String {
-        let mut repr = self.name.clone();
+        String::from_utf8_lossy(&self.stdout_bytes()).into_owned()
+    }
+
+    /// The exact bytes to write to stdout when outputting this entry.
+    ///
+    /// If the entry has an `output`, it is written as is.
+    pub fn stdout_bytes(&self) -> Vec<u8> {
+        if let Some(output) = &self.output {
+            return output.clone().into_bytes();
+        }
+        let mut repr = self
+            .raw
+            .clone()
+            .unwrap_or_else(|| self.name.clone().into_bytes());
         if let Some(line_number) = self.line_number {
-            repr.push_str(&format!(":{line_number}"));
+            repr.extend_from_slice(format!(":{line_number}").as_bytes());
         }
         repr
     }
@@ -172,8 +263,12 @@ mod tests {
             value: None,
             name_match_ranges: None,
             value_match_ranges: None,
+            name_styles: None,
             icon: None,
             line_number: None,
+            raw: None,
+            display: None,
+            output: None,
         };
         assert_eq!(entry.stdout_repr(), "test name with spaces");
     }
@@ -185,9 +280,46 @@ mod tests {
             value: None,
             name_match_ranges: None,
             value_match_ranges: None,
+            name_styles: None,
             icon: None,
             line_number: Some(a),
+            raw: None,
+            display: None,
+            output: None,
         };
         assert_eq!(entry.stdout_repr(), "test_file_name.rs:10");
     }
+
+    #[test]
+    fn test_from_bytes_valid_utf8() {
+        let entry = Entry::from_bytes(b"some file.txt".to_vec());
+        assert_eq!(entry.name, "some file.txt");
+        assert_eq!(entry.raw, None);
+        assert_eq!(entry.stdout_bytes(), b"some file.txt");
+    }
+
+    #[test]
+    fn test_from_bytes_keeps_invalid_utf8() {
+        let entry =
+            Entry::from_bytes(b"caf\xe9.txt".to_vec()).with_line_number(3);
+        assert_eq!(entry.name, "caf\u{FFFD}.txt");
+        assert_eq!(entry.stdout_bytes(), b"caf\xe9.txt:3");
+        assert_eq!(entry.stdout_repr(), "caf\u{FFFD}.txt:3");
+    }
+
+    #[test]
+    fn test_entries_with_same_lossy_name_differ() {
+        let a = Entry::from_bytes(b"\xe9".to_vec());
+        let b = Entry::from_bytes(b"\xe8".to_vec());
+        assert_eq!(a.name, b.name);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_output_replaces_name() {
+        let entry = Entry::new("1a2b nginx".to_string())
+            .with_line_number(3)
+            .with_output(Some("1a2b".to_string()));
+        assert_eq!(entry.stdout_repr(), "1a2b");
+    }
 }
@@ -0,0 +1,416 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::channels::{
+    preview::DEFAULT_DELIMITER, prototypes::ChannelPrototype,
+};
+use crate::utils::{
+    ansi::StyledRange,
+    strings::{format_template, PLACEHOLDER_RE},
+};
+
+/// The fields of a channel's entries that are matched against, displayed
+/// and output.
+///
+/// Entries are split into fields using the channel's preview delimiter and
+/// fields are referenced by their index, starting at 0, the same way they
+/// are in preview commands (e.g. `{0}`). Selected fields are joined back
+/// together using the delimiter, and fields that an entry doesn't have are
+/// left out.
+///
+/// When neither `match_fields` nor `display_fields` are set, entries are
+/// matched against and displayed as a whole.
+///
+/// Entries can also be split into the channel's named `columns` which are
+/// matched separately. The last column gets the rest of the entry,
+/// delimiters included.
+///
+/// Finally, the channel's `display` and `output` templates (see
+/// `format_template`) take precedence over `display_fields` and over the
+/// entry itself respectively.
+///
+/// # Example
+/// ```
+/// use television::channels::{fields::Fields, prototypes::ChannelPrototype};
+///
+/// let mut prototype = ChannelPrototype::new("log", "git log", false, None);
+/// prototype.match_fields = Some(vec![1, 2]);
+/// prototype.display_fields = Some(vec![0, 1]);
+/// let fields = Fields::new(&prototype);
+///
+/// assert_eq!(fields.matched("1a2b fix bug"), "fix bug");
+/// // "fix" was matched which is displayed after the hash
+/// let (display, indices) = fields.display("1a2b fix bug", &[0, 1, 2], &[]);
+/// assert_eq!(display.as_deref(), Some("1a2b fix"));
+/// assert_eq!(indices, vec![5, 6, 7]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Fields {
+    delimiter: String,
+    matched: Option<Vec<usize>>,
+    displayed: Option<Vec<usize>>,
+    /// The number of named columns.
+    columns: usize,
+    display_template: Option<String>,
+    output_template: Option<String>,
+}
+
+impl Fields {
+    pub fn new(prototype: &ChannelPrototype) -> Self {
+        let delimiter = prototype
+            .preview_command
+            .as_ref()
+            .map_or(DEFAULT_DELIMITER, |p| p.delimiter.as_str());
+        Self {
+            delimiter: delimiter.to_string(),
+            matched: prototype.match_fields.clone(),
+            displayed: prototype.display_fields.clone(),
+            columns: prototype.columns.as_ref().map_or(0, Vec::len),
+            display_template: prototype.display.clone(),
+            output_template: prototype.output.clone(),
+        }
+    }
+
+    /// What to output for the given line, if it differs from the line
+    /// itself.
+    pub fn output(&self, line: &str) -> Option<String> {
+        self.output_template
+            .as_ref()
+            .map(|template| format_template(template, line, &self.delimiter))
+    }
+
+    /// The given line split into the channel's named columns.
+    ///
+    /// Columns that the line doesn't have are empty.
+    pub fn columns<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        let mut columns = line
+            .splitn(self.columns, self.delimiter.as_str())
+            .collect::<Vec<_>>();
+        columns.resize(self.columns, "");
+        columns
+    }
+
+    /// The part of the given line that is matched against.
+    pub fn matched<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        match &self.matched {
+            None => Cow::Borrowed(line),
+            Some(fields) => Cow::Owned(self.select(line, fields)),
+        }
+    }
+
+    /// The text to display for the given line, if it differs from the line
+    /// itself, along with the indices of the matched characters translated
+    /// to that text from the matched part of the line (see `matched`) and
+    /// from each of its columns (see `columns`).
+    ///
+    /// Matched characters that aren't displayed are dropped.
+    pub fn display(
+        &self,
+        line: &str,
+        match_indices: &[u32],
+        column_indices: &[Vec<u32>],
+    ) -> (Option<String>, Vec<u32>) {
+        if self.matched.is_none()
+            && self.displayed.is_none()
+            && self.display_template.is_none()
+            && column_indices.iter().all(Vec::is_empty)
+        {
+            return (None, match_indices.to_vec());
+        }
+        // all of the line's fields joined together make up the whole line
+        let spans = self.spans(line);
+        let matched = self
+            .matched
+            .as_ref()
+            .map_or_else(|| spans.clone(), |fields| selected(&spans, fields));
+        let delimiter_len = self.delimiter.chars().count();
+        let (display, displayed) = self.layout(line, &spans);
+
+        let line_indices = match_indices
+            .iter()
+            .filter_map(|i| to_line(&matched, delimiter_len, *i as usize))
+            .chain(column_indices.iter().zip(&spans).flat_map(
+                |(indices, span)| {
+                    indices.iter().map(|i| span.start + *i as usize)
+                },
+            ));
+        let mut indices = Vec::new();
+        for index in line_indices {
+            indices.extend(
+                displayed
+                    .iter()
+                    .filter(|(span, _)| span.contains(&index))
+                    .map(|(span, offset)| offset + index - span.start)
+                    .map(|i| u32::try_from(i).unwrap_or(u32::MAX)),
+            );
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        (display, indices)
+    }
+
+    /// Translate the given styled ranges of characters of the line to the
+    /// text displayed for it (see `display`).
+    pub fn display_styles(
+        &self,
+        line: &str,
+        styles: &[StyledRange],
+    ) -> Vec<StyledRange> {
+        if self.displayed.is_none() && self.display_template.is_none() {
+            return styles.to_vec();
+        }
+        let (_, displayed) = self.layout(line, &self.spans(line));
+        let mut translated = Vec::new();
+        for (start, end, style) in styles {
+            let styled = *start as usize..*end as usize;
+            for (span, offset) in &displayed {
+                let start = styled.start.max(span.start);
+                let end = styled.end.min(span.end);
+                if start < end {
+                    translated.push((
+                        u32::try_from(offset + start - span.start).unwrap(),
+                        u32::try_from(offset + end - span.start).unwrap(),
+                        *style,
+                    ));
+                }
+            }
+        }
+        translated.sort_unstable_by_key(|(start, _, _)| *start);
+        translated
+    }
+
+    /// The text to display for the line, if it differs from the line itself,
+    /// along with where the line's spans end up in that text (see
+    /// `joined`), given the spans of the line's fields.
+    fn layout(
+        &self,
+        line: &str,
+        spans: &[Range<usize>],
+    ) -> (Option<String>, Vec<(Range<usize>, usize)>) {
+        let delimiter_len = self.delimiter.chars().count();
+        match (&self.display_template, &self.displayed) {
+            (Some(template), _) => {
+                let (display, placed) = render(template, line, spans);
+                (Some(display), placed)
+            }
+            (None, Some(fields)) => (
+                Some(self.select(line, fields)),
+                joined(&selected(spans, fields), delimiter_len),
+            ),
+            (None, None) => (None, joined(spans, delimiter_len)),
+        }
+    }
+
+    /// The given fields of the line, joined with the delimiter.
+    fn select(&self, line: &str, fields: &[usize]) -> String {
+        let parts = line.split(self.delimiter.as_str()).collect::<Vec<_>>();
+        fields
+            .iter()
+            .filter_map(|field| parts.get(*field).copied())
+            .collect::<Vec<_>>()
+            .join(&self.delimiter)
+    }
+
+    /// The ranges of characters covered by each of the line's fields.
+    fn spans(&self, line: &str) -> Vec<Range<usize>> {
+        let delimiter_len = self.delimiter.chars().count();
+        let mut start = 0;
+        line.split(self.delimiter.as_str())
+            .map(|part| {
+                let end = start + part.chars().count();
+                let span = start..end;
+                start = end + delimiter_len;
+                span
+            })
+            .collect()
+    }
+}
+
+/// The spans of the given fields, leaving out the ones that don't exist.
+fn selected(spans: &[Range<usize>], fields: &[usize]) -> Vec<Range<usize>> {
+    fields
+        .iter()
+        .filter_map(|field| spans.get(*field).cloned())
+        .collect()
+}
+
+/// Translate an index in the given spans joined with a delimiter into an
+/// index in the line the spans come from.
+///
+/// Indices pointing at a delimiter added when joining the spans have no
+/// counterpart in the line.
+fn to_line(
+    spans: &[Range<usize>],
+    delimiter_len: usize,
+    mut index: usize,
+) -> Option<usize> {
+    for span in spans {
+        if index < span.len() {
+            return Some(span.start + index);
+        }
+        index = index.checked_sub(span.len() + delimiter_len)?;
+    }
+    None
+}
+
+/// Where the given spans of a line end up once joined with a delimiter, as
+/// pairs of spans and offsets in the joined text.
+fn joined(
+    spans: &[Range<usize>],
+    delimiter_len: usize,
+) -> Vec<(Range<usize>, usize)> {
+    let mut offset = 0;
+    spans
+        .iter()
+        .map(|span| {
+            let placed = (span.clone(), offset);
+            offset += span.len() + delimiter_len;
+            placed
+        })
+        .collect()
+}
+
+/// Format the given template with the line (see `format_template`), keeping
+/// track of where the line's spans (`spans` being the spans of its fields)
+/// end up in the formatted text.
+fn render(
+    template: &str,
+    line: &str,
+    spans: &[Range<usize>],
+) -> (String, Vec<(Range<usize>, usize)>) {
+    let whole_line = 0..line.chars().count();
+    let mut rendered = String::new();
+    let mut placed = Vec::new();
+    let mut last_end = 0;
+    for caps in PLACEHOLDER_RE.captures_iter(template) {
+        // the unwrap is safe since there is always a whole match
+        let placeholder = caps.get(0).unwrap();
+        rendered.push_str(&template[last_end..placeholder.start()]);
+        last_end = placeholder.end();
+        let span = match caps[1].parse::<usize>() {
+            Ok(field) => match spans.get(field) {
+                Some(span) => span.clone(),
+                None => continue,
+            },
+            Err(_) => whole_line.clone(),
+        };
+        placed.push((span.clone(), rendered.chars().count()));
+        rendered.extend(line.chars().skip(span.start).take(span.len()));
+    }
+    rendered.push_str(&template[last_end..]);
+    (rendered, placed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::preview::PreviewCommand;
+    use ratatui::style::{Color, Style};
+
+    fn fields(
+        delimiter: &str,
+        matched: Option<Vec<usize>>,
+        displayed: Option<Vec<usize>>,
+    ) -> Fields {
+        let mut prototype = ChannelPrototype::new(
+            "test",
+            "cat",
+            false,
+            Some(PreviewCommand::new("cat {0}", delimiter, None)),
+        );
+        prototype.match_fields = matched;
+        prototype.display_fields = displayed;
+        Fields::new(&prototype)
+    }
+
+    #[test]
+    fn test_whole_line_by_default() {
+        let fields = fields(":", None, None);
+        assert_eq!(fields.matched("a:b:c"), "a:b:c");
+        assert_eq!(fields.display("a:b:c", &[0, 2], &[]), (None, vec![0, 2]));
+    }
+
+    #[test]
+    fn test_matched_fields() {
+        let fields = fields("\t", Some(vec![2, 0, 5]), None);
+        assert_eq!(fields.matched("a\tbb\tccc"), "ccc\ta");
+        // "c" and "a" in the whole line
+        assert_eq!(
+            fields.display("a\tbb\tccc", &[1, 4], &[]),
+            (None, vec![0, 6])
+        );
+        // the delimiter between the matched fields isn't in the line
+        assert_eq!(fields.display("a\tbb\tccc", &[3], &[]), (None, vec![]));
+    }
+
+    #[test]
+    fn test_displayed_fields() {
+        let fields = fields(" :: ", Some(vec![1, 2]), Some(vec![0, 2]));
+        let line = "héllo :: wörld :: again";
+        assert_eq!(fields.matched(line), "wörld :: again");
+        let (display, indices) = fields.display(line, &[0, 9, 10], &[]);
+        assert_eq!(display.as_deref(), Some("héllo :: again"));
+        // "w" isn't displayed
+        assert_eq!(indices, vec![9, 10]);
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut prototype = ChannelPrototype::new(
+            "test",
+            "cat",
+            false,
+            Some(PreviewCommand::new("cat {0}", ":", None)),
+        );
+        prototype.columns =
+            Some(vec!["path".into(), "line".into(), "content".into()]);
+        prototype.display_fields = Some(vec![0, 2]);
+        let fields = Fields::new(&prototype);
+
+        let line = "src/main.rs:12:a: todo";
+        assert_eq!(fields.columns(line), vec!["src/main.rs", "12", "a: todo"]);
+        assert_eq!(fields.columns("foo"), vec!["foo", "", ""]);
+        // "main" in the path and "todo" in the content, which isn't displayed
+        let (display, indices) =
+            fields.display(line, &[], &[vec![4, 5, 6, 7], vec![], vec![3, 4]]);
+        assert_eq!(display.as_deref(), Some("src/main.rs:a"));
+        assert_eq!(indices, vec![4, 5, 6, 7]);
+        let (display, indices) = fields.display(line, &[], &[vec![], vec![0]]);
+        assert_eq!(display.as_deref(), Some("src/main.rs:a"));
+        assert_eq!(indices, Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_templates() {
+        let mut prototype = ChannelPrototype::new(
+            "test",
+            "cat",
+            false,
+            Some(PreviewCommand::new("cat {0}", "\t", None)),
+        );
+        prototype.match_fields = Some(vec![1]);
+        prototype.display = Some("{1}  ({2}{3})".to_string());
+        prototype.output = Some("{0}".to_string());
+        let fields = Fields::new(&prototype);
+
+        let line = "1a2b\tnginx:latest\t12MB";
+        assert_eq!(fields.output(line).as_deref(), Some("1a2b"));
+        let (display, indices) = fields.display(line, &[0, 6], &[]);
+        assert_eq!(display.as_deref(), Some("nginx:latest  (12MB)"));
+        assert_eq!(indices, vec![0, 6]);
+        assert_eq!(fields.output("none"), Some("none".to_string()));
+    }
+
+    #[test]
+    fn test_display_styles() {
+        let red = Style::default().fg(Color::Red);
+        let fields = fields(":", None, Some(vec![2, 0]));
+        let (display, _) = fields.display("ab:c:de", &[], &[]);
+        assert_eq!(display.as_deref(), Some("de:ab"));
+        // "b:c:d" is red
+        assert_eq!(
+            fields.display_styles("ab:c:de", &[(1, 6, red)]),
+            vec![(0, 1, red), (4, 5, red)]
+        );
+    }
+}
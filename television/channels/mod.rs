@@ -1,5 +1,7 @@
+pub mod actions;
 pub mod cable;
 pub mod entry;
+pub mod fields;
 pub mod preview;
 pub mod prototypes;
 pub mod remote_control;
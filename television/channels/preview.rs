@@ -2,7 +2,10 @@ use std::fmt::Display;
 
 use serde::Deserialize;
 
-use crate::{channels::entry::Entry, utils::strings::format_string};
+use crate::{
+    channels::entry::Entry, previewer::PreviewSize,
+    utils::strings::format_string,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Default, Deserialize)]
 pub struct PreviewCommand {
@@ -15,6 +18,11 @@ pub struct PreviewCommand {
 
 pub const DEFAULT_DELIMITER: &str = " ";
 
+/// The placeholders for the number of columns and lines the preview is
+/// displayed in, e.g. `bat --terminal-width {columns} {}`.
+pub const PREVIEW_COLUMNS_PLACEHOLDER: &str = "{columns}";
+pub const PREVIEW_LINES_PLACEHOLDER: &str = "{lines}";
+
 /// The default delimiter to use for the preview command to use to split
 /// entries into multiple referenceable parts.
 #[allow(clippy::unnecessary_wraps)]
@@ -55,6 +63,20 @@ impl PreviewCommand {
     pub fn format_with(&self, entry: &Entry) -> String {
         format_string(&self.command, &entry.name, &self.delimiter)
     }
+
+    /// Format the command with the entry name and provided placeholders as
+    /// well as the size of the preview.
+    pub fn format_with_size(
+        &self,
+        entry: &Entry,
+        size: PreviewSize,
+    ) -> String {
+        let command = self
+            .command
+            .replace(PREVIEW_COLUMNS_PLACEHOLDER, &size.columns.to_string())
+            .replace(PREVIEW_LINES_PLACEHOLDER, &size.lines.to_string());
+        format_string(&command, &entry.name, &self.delimiter)
+    }
 }
 
 impl Display for PreviewCommand {
@@ -84,6 +106,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_command_with_size() {
+        let command = PreviewCommand {
+            command: "bat -w {columns} -n {lines} {0}".to_string(),
+            delimiter: ":".to_string(),
+            offset_expr: None,
+        };
+        let entry = Entry::new("{columns}:entry".to_string());
+        let formatted_command = command.format_with_size(
+            &entry,
+            PreviewSize {
+                columns: 80,
+                lines: 20,
+            },
+        );
+
+        assert_eq!(formatted_command, "bat -w 80 -n 20 '{columns}'");
+    }
+
     #[test]
     fn test_format_command_no_placeholders() {
         let command = PreviewCommand {
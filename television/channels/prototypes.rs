@@ -1,12 +1,24 @@
 use rustc_hash::FxHashMap;
 use std::{
+    collections::BTreeMap,
     fmt::{self, Display, Formatter},
     ops::Deref,
+    time::Duration,
 };
 
 use crate::{
-    cable::CableSpec, channels::preview::PreviewCommand,
+    cable::CableSpec,
+    channels::{
+        actions::ActionSpec,
+        preview::{PreviewCommand, DEFAULT_DELIMITER},
+    },
     cli::unknown_channel_exit,
+    matcher::{
+        config::{Config as MatcherConfig, TieBreak},
+        promotion::PromotionMode,
+    },
+    previewer::Config as PreviewerConfig,
+    television::MatchingMode,
 };
 
 /// A prototype for cable channels.
@@ -18,19 +30,71 @@ use crate::{
 /// - `name`: The name of the channel. This will be used to identify the
 ///     channel throughout the application and in UI menus.
 /// - `source_command`: The command to run to get the source for the channel.
-///     This is a shell command that will be run in the background.
+///     This is a shell command that will be run in the background. If it
+///     contains the `{q}` placeholder, it is re-run with the user's query
+///     each time the query changes (e.g. `rg --line-number {q}`). Input
+///     after a ` // ` separator isn't passed to the command but fuzzy
+///     matched against its results instead (e.g. `foo // bar`). The command
+///     only runs once the query was left untouched for `query_debounce`
+///     milliseconds, which overrides the configuration file's. Entries
+///     sent over from another channel replace the `{+}` placeholder if there
+///     is one (e.g. `fd -t f . {+}`) and are piped to its stdin otherwise.
 /// - `interactive`: Whether the source command should be run in an interactive
 ///     shell. This is useful for commands that need the user's environment e.g.
 ///     `alias`.
+/// - `read0`: Whether the output of the source command is separated by NUL
+///     characters instead of newlines. This is useful for sources that may
+///     produce entries containing newlines (e.g. `fd -0`).
+/// - `ansi`: Whether the output of the source command contains ANSI escape
+///     codes (e.g. `rg --color=always`). Entries are then matched against
+///     and output without them while their colors are kept in the results.
+/// - `watch`: An optional interval (in seconds) at which the source command
+///     should be re-run to keep the channel's entries up to date.
 /// - `preview_command`: The command to run on each entry to get the preview
 ///     for the channel. If this is not `None`, the channel will display a preview
-///     pane with the output of this command.
+///     pane with the output of this command. The size of the preview is
+///     available to it through the `{columns}` and `{lines}` placeholders as
+///     well as the `TV_PREVIEW_COLUMNS` and `TV_PREVIEW_LINES` environment
+///     variables.
 /// - `preview_delimiter`: The delimiter to use to split an entry into
 ///     multiple parts that can then be referenced in the preview command (e.g.
 ///     `{1} + {2}`).
 /// - `preview_offset`: a litteral expression that will be interpreted later on
 ///     in order to determine the vertical offset at which the preview should be
 ///     displayed.
+/// - `request_max_age`, `job_timeout` and `debounce`: Override the previewer's
+///     timings from the `[previewer]` section of the configuration file for
+///     this channel, in milliseconds (e.g. `job_timeout = 2000` for slow
+///     preview commands).
+/// - `pty`: Whether the preview command runs inside a pseudo-terminal of the
+///     size of the preview, overriding the `[previewer]` section of the
+///     configuration file for this channel (e.g. for `ls --color=auto`).
+/// - `actions`: Custom actions that can be triggered on the channel's entries
+///     using the given keys (see `ActionSpec`).
+/// - `frecency`: Whether the entries picked most often and most recently
+///     should be ranked higher, either above all other results (`"boost"`)
+///     or only above the results that match equally well (`"tie-breaker"`).
+/// - `matching_mode`: How the user's input is matched against the channel's
+///     entries when switching to it (`"fuzzy"`, `"substring"`, `"prefix"`,
+///     `"case-sensitive"` or `"regex"`).
+/// - `prefer_prefix`, `match_paths` and `ignore_case`: Override the matcher's
+///     settings from the `[matcher]` section of the configuration file for
+///     this channel (e.g. `match_paths = true` for channels listing paths).
+/// - `sort`: Whether matches should be ranked by score or kept in the order
+///     the source command outputs them (e.g. `sort = false` for logs and
+///     histories).
+/// - `tiebreak`: How matches with the same score are ordered, e.g.
+///     `["path-depth", "length"]` (see `TieBreak`).
+/// - `match_fields` and `display_fields`: Which of an entry's fields, split
+///     with the preview delimiter, are matched against and displayed (e.g.
+///     `[1, 2]`). Entries are still output as a whole (see `Fields`).
+/// - `columns`: Names for the fields of the channel's entries, split with the
+///     preview delimiter, so that words of the query prefixed with a name
+///     are only matched against that field (e.g. `path:src content:todo`).
+///     The last column gets the rest of the entry.
+/// - `display` and `output`: Templates for what is displayed and output for
+///     each entry, with the same placeholders as the preview command (e.g.
+///     `display = "{1}  {2}"` and `output = "{0}"`) but without quoting.
 ///
 /// # Example
 /// The default files channel might look something like this:
@@ -46,8 +110,50 @@ pub struct ChannelPrototype {
     pub source_command: String,
     #[serde(default)]
     pub interactive: bool,
+    #[serde(default)]
+    pub read0: bool,
+    #[serde(default)]
+    pub ansi: bool,
+    #[serde(default)]
+    pub watch: Option<f64>,
     #[serde(rename = "preview")]
     pub preview_command: Option<PreviewCommand>,
+    #[serde(default)]
+    pub actions: BTreeMap<String, ActionSpec>,
+    #[serde(default)]
+    pub frecency: Option<PromotionMode>,
+    #[serde(default)]
+    pub matching_mode: Option<MatchingMode>,
+    #[serde(default)]
+    pub prefer_prefix: Option<bool>,
+    #[serde(default)]
+    pub match_paths: Option<bool>,
+    #[serde(default)]
+    pub ignore_case: Option<bool>,
+    #[serde(default)]
+    pub sort: Option<bool>,
+    #[serde(default)]
+    pub tiebreak: Option<Vec<TieBreak>>,
+    #[serde(default)]
+    pub match_fields: Option<Vec<usize>>,
+    #[serde(default)]
+    pub display_fields: Option<Vec<usize>>,
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    #[serde(default)]
+    pub display: Option<String>,
+    #[serde(default)]
+    pub output: Option<String>,
+    #[serde(default)]
+    pub request_max_age: Option<u64>,
+    #[serde(default)]
+    pub job_timeout: Option<u64>,
+    #[serde(default)]
+    pub debounce: Option<u64>,
+    #[serde(default)]
+    pub pty: Option<bool>,
+    #[serde(default)]
+    pub query_debounce: Option<u64>,
 }
 
 const STDIN_CHANNEL_NAME: &str = "stdin";
@@ -64,26 +170,160 @@ impl ChannelPrototype {
             name: name.to_string(),
             source_command: source_command.to_string(),
             interactive,
+            read0: false,
+            ansi: false,
+            watch: None,
             preview_command,
+            actions: BTreeMap::new(),
+            frecency: None,
+            matching_mode: None,
+            prefer_prefix: None,
+            match_paths: None,
+            ignore_case: None,
+            sort: None,
+            tiebreak: None,
+            match_fields: None,
+            display_fields: None,
+            columns: None,
+            display: None,
+            output: None,
+            request_max_age: None,
+            job_timeout: None,
+            debounce: None,
+            pty: None,
+            query_debounce: None,
         }
     }
 
     pub fn stdin(preview: Option<PreviewCommand>) -> Self {
-        Self {
-            name: STDIN_CHANNEL_NAME.to_string(),
-            source_command: STDIN_SOURCE_COMMAND.to_string(),
-            interactive: false,
-            preview_command: preview,
-        }
+        Self::new(STDIN_CHANNEL_NAME, STDIN_SOURCE_COMMAND, false, preview)
     }
 
     pub fn set_preview(self, preview_command: Option<PreviewCommand>) -> Self {
-        Self::new(
-            &self.name,
-            &self.source_command,
-            self.interactive,
+        Self {
             preview_command,
-        )
+            ..self
+        }
+    }
+
+    pub fn with_read0(mut self, read0: bool) -> Self {
+        self.read0 = read0;
+        self
+    }
+
+    pub fn with_ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    pub fn with_watch(mut self, watch: Option<f64>) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    pub fn with_sort(mut self, sort: Option<bool>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn with_frecency(mut self, frecency: Option<PromotionMode>) -> Self {
+        self.frecency = frecency;
+        self
+    }
+
+    pub fn with_matching_mode(
+        mut self,
+        matching_mode: Option<MatchingMode>,
+    ) -> Self {
+        self.matching_mode = matching_mode;
+        self
+    }
+
+    /// The interval at which the source command should be re-run, if any.
+    ///
+    /// Intervals that aren't positive disable watching, while those that
+    /// aren't a valid duration (e.g. `nan` or `inf`) are reported.
+    pub fn watch_interval(&self) -> Result<Option<Duration>, String> {
+        match self.watch {
+            Some(secs) if secs.is_nan() => {
+                Err("invalid watch interval: nan".to_string())
+            }
+            Some(secs) if secs > 0.0 => Duration::try_from_secs_f64(secs)
+                .map(Some)
+                .map_err(|e| format!("invalid watch interval {secs}: {e}")),
+            _ => Ok(None),
+        }
+    }
+
+    /// Apply the channel's matcher overrides to the given configuration.
+    pub fn matcher_config(&self, mut config: MatcherConfig) -> MatcherConfig {
+        if let Some(prefer_prefix) = self.prefer_prefix {
+            config = config.prefer_prefix(prefer_prefix);
+        }
+        if let Some(match_paths) = self.match_paths {
+            config = config.match_paths(match_paths);
+        }
+        if let Some(ignore_case) = self.ignore_case {
+            config = config.ignore_case(ignore_case);
+        }
+        if let Some(sort) = self.sort {
+            config = config.sort(sort);
+        }
+        if let Some(tiebreak) = &self.tiebreak {
+            config = config.tiebreak(tiebreak.clone());
+        }
+        config
+    }
+
+    /// Apply the channel's previewer overrides to the given configuration.
+    pub fn previewer_config(
+        &self,
+        mut config: PreviewerConfig,
+    ) -> PreviewerConfig {
+        if let Some(request_max_age) = self.request_max_age {
+            config =
+                config.request_max_age(Duration::from_millis(request_max_age));
+        }
+        if let Some(job_timeout) = self.job_timeout {
+            config = config.job_timeout(Duration::from_millis(job_timeout));
+        }
+        if let Some(debounce) = self.debounce {
+            config = config.debounce(Duration::from_millis(debounce));
+        }
+        if let Some(pty) = self.pty {
+            config = config.pty(pty);
+        }
+        config
+    }
+
+    /// The channel's custom actions, those without a delimiter of their own
+    /// splitting entries the same way the preview command does.
+    pub fn resolved_actions(&self) -> BTreeMap<String, ActionSpec> {
+        let delimiter = self
+            .preview_command
+            .as_ref()
+            .map_or(DEFAULT_DELIMITER, |p| p.delimiter.as_str());
+        self.actions
+            .iter()
+            .map(|(name, action)| {
+                let mut action = action.clone();
+                action
+                    .delimiter
+                    .get_or_insert_with(|| delimiter.to_string());
+                (name.clone(), action)
+            })
+            .collect()
+    }
+
+    /// How long the query has to stay untouched before the source command
+    /// is run with it, given the configured default.
+    pub fn query_debounce(&self, default: Duration) -> Duration {
+        self.query_debounce.map_or(default, Duration::from_millis)
+    }
+
+    pub fn with_action(mut self, name: &str, action: ActionSpec) -> Self {
+        self.actions.insert(name.to_string(), action);
+        self
     }
 }
 
@@ -141,6 +141,46 @@ pub struct Cli {
     #[arg(long, default_value = "false", verbatim_doc_comment)]
     pub no_help: bool,
 
+    /// Read input delimited by NUL characters instead of newlines.
+    ///
+    /// This applies to both the source command of the selected channel and
+    /// stdin and makes it possible to handle entries containing newlines,
+    /// e.g. `fd -0 | tv --read0 --print0 | xargs -0`.
+    #[arg(long, default_value = "false", verbatim_doc_comment)]
+    pub read0: bool,
+
+    /// Parse ANSI escape codes in the input.
+    ///
+    /// This applies to both the source command of the selected channel and
+    /// stdin: entries keep their colors in the results list while being
+    /// matched against and printed without escape codes,
+    /// e.g. `rg --color=always foo | tv --ansi`.
+    #[arg(long, default_value = "false", verbatim_doc_comment)]
+    pub ansi: bool,
+
+    /// Separate the selected entries with NUL characters instead of newlines
+    /// when printing them to stdout.
+    ///
+    /// This is useful when piping the output to tools such as `xargs -0`.
+    #[arg(long, default_value = "false", verbatim_doc_comment)]
+    pub print0: bool,
+
+    /// Keep matches in the order the source outputs them instead of ranking
+    /// them by score.
+    ///
+    /// This applies to the selected channel and stdin and is useful for
+    /// sources that are already in a meaningful order (e.g. logs).
+    #[arg(long, default_value = "false", verbatim_doc_comment)]
+    pub no_sort: bool,
+
+    /// The number of threads the fuzzy matcher should use.
+    ///
+    /// This defaults to the number of available logical cores and can be
+    /// lowered on constrained machines (e.g. CI runners using `tv` in
+    /// scripts).
+    #[arg(long, value_name = "INTEGER", verbatim_doc_comment)]
+    pub threads: Option<usize>,
+
     /// Change the display size in relation to the available area.
     ///
     /// This will crop the UI to a centered rectangle of the specified
@@ -34,6 +34,11 @@ pub struct PostProcessedCli {
     pub select_1: bool,
     pub no_remote: bool,
     pub no_help: bool,
+    pub read0: bool,
+    pub ansi: bool,
+    pub print0: bool,
+    pub no_sort: bool,
+    pub threads: Option<usize>,
     pub ui_scale: u16,
 }
 
@@ -55,6 +60,11 @@ impl Default for PostProcessedCli {
             select_1: false,
             no_remote: false,
             no_help: false,
+            read0: false,
+            ansi: false,
+            print0: false,
+            no_sort: false,
+            threads: None,
             ui_scale: 100,
         }
     }
@@ -102,6 +112,11 @@ pub fn post_process(cli: Cli, cable: &Cable) -> PostProcessedCli {
         select_1: cli.select_1,
         no_remote: cli.no_remote,
         no_help: cli.no_help,
+        read0: cli.read0,
+        ansi: cli.ansi,
+        print0: cli.print0,
+        no_sort: cli.no_sort,
+        threads: cli.threads,
         ui_scale: cli.ui_scale,
     }
 }
@@ -15,7 +15,11 @@ pub use themes::Theme;
 use tracing::{debug, warn};
 pub use ui::UiConfig;
 
-use crate::channels::prototypes::DEFAULT_PROTOTYPE_NAME;
+use crate::{
+    channels::prototypes::DEFAULT_PROTOTYPE_NAME,
+    matcher::config::Config as MatcherConfig,
+    previewer::Config as PreviewerConfig,
+};
 
 mod keybindings;
 pub mod shell_integration;
@@ -39,18 +43,36 @@ pub struct AppConfig {
     /// The default channel to use when no channel is specified
     #[serde(default = "default_channel")]
     pub default_channel: String,
+    /// The maximum number of queries remembered for each channel (`0`
+    /// disables the query history)
+    #[serde(default = "default_history_size")]
+    pub history_size: usize,
+    /// How long the query has to stay untouched before the source command
+    /// of channels using it (see `{q}`) is run again, in milliseconds
+    #[serde(default = "default_query_debounce")]
+    pub query_debounce: u64,
 }
 
 fn default_channel() -> String {
     DEFAULT_PROTOTYPE_NAME.to_string()
 }
 
+fn default_history_size() -> usize {
+    100
+}
+
+fn default_query_debounce() -> u64 {
+    150
+}
+
 impl Hash for AppConfig {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.data_dir.hash(state);
         self.config_dir.hash(state);
         self.frame_rate.to_bits().hash(state);
         self.tick_rate.to_bits().hash(state);
+        self.history_size.hash(state);
+        self.query_debounce.hash(state);
     }
 }
 
@@ -70,6 +92,12 @@ pub struct Config {
     /// Shell integration configuration
     #[serde(default)]
     pub shell_integration: ShellIntegrationConfig,
+    /// Fuzzy matcher configuration
+    #[serde(default)]
+    pub matcher: MatcherConfig,
+    /// Previewer configuration
+    #[serde(default)]
+    pub previewer: PreviewerConfig,
 }
 
 const PROJECT_NAME: &str = "television";
@@ -196,6 +224,8 @@ impl Config {
             keybindings: user.keybindings,
             ui: user.ui,
             shell_integration: user.shell_integration,
+            matcher: user.matcher,
+            previewer: user.previewer,
         }
     }
 }
@@ -275,6 +305,7 @@ mod tests {
     use rustc_hash::FxHashMap;
     use std::fs::File;
     use std::io::Write;
+    use std::time::Duration;
     use tempfile::tempdir;
 
     #[test]
@@ -411,6 +442,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_user_matcher_config() {
+        let user_config = r"
+            [matcher]
+            prefer_prefix = true
+            threads = 2
+        ";
+
+        let dir = tempdir().unwrap();
+        let config_dir = dir.path();
+        let config_file = config_dir.join(CONFIG_FILE_NAME);
+        let mut file = File::create(&config_file).unwrap();
+        file.write_all(user_config.as_bytes()).unwrap();
+
+        let config_env = ConfigEnv {
+            _data_dir: get_data_dir(),
+            config_dir: config_dir.to_path_buf(),
+        };
+
+        let config = Config::new(&config_env).unwrap();
+
+        assert_eq!(
+            config.matcher,
+            MatcherConfig::default().prefer_prefix(true).n_threads(2)
+        );
+    }
+
+    #[test]
+    fn test_user_previewer_config() {
+        let user_config = r"
+            [previewer]
+            cache_size = 10
+            prefetch = 0
+            job_timeout = 2000
+            debounce = 50
+        ";
+
+        let dir = tempdir().unwrap();
+        let config_dir = dir.path();
+        let config_file = config_dir.join(CONFIG_FILE_NAME);
+        let mut file = File::create(&config_file).unwrap();
+        file.write_all(user_config.as_bytes()).unwrap();
+
+        let config_env = ConfigEnv {
+            _data_dir: get_data_dir(),
+            config_dir: config_dir.to_path_buf(),
+        };
+
+        let config = Config::new(&config_env).unwrap();
+
+        assert_eq!(
+            config.previewer,
+            PreviewerConfig::default()
+                .cache_size(10)
+                .prefetch(0)
+                .job_timeout(Duration::from_millis(2000))
+                .debounce(Duration::from_millis(50))
+        );
+    }
+
     #[test]
     fn test_setting_user_shell_integration_triggers_overrides_default() {
         let user_config = r#"
@@ -94,6 +94,8 @@ pub struct Theme {
     pub border_fg: Color,
     pub text_fg: Color,
     pub dimmed_text_fg: Color,
+    pub error_fg: Color,
+    pub warning_fg: Color,
     // input
     pub input_text_fg: Color,
     pub result_count_fg: Color,
@@ -109,6 +111,7 @@ pub struct Theme {
     // modes
     pub channel_mode_fg: Color,
     pub remote_control_mode_fg: Color,
+    pub send_to_channel_mode_fg: Color,
 }
 
 impl Theme {
@@ -162,6 +165,12 @@ struct Inner {
     // info
     text_fg: String,
     dimmed_text_fg: String,
+    // this is made optional for theme backwards compatibility
+    // and falls back to red
+    error_fg: Option<String>,
+    // this is made optional for theme backwards compatibility
+    // and falls back to yellow
+    warning_fg: Option<String>,
     // input
     input_text_fg: String,
     result_count_fg: String,
@@ -179,6 +188,9 @@ struct Inner {
     //modes
     channel_mode_fg: String,
     remote_control_mode_fg: String,
+    // this is made optional for theme backwards compatibility
+    // and falls back to remote_control_mode_fg
+    send_to_channel_mode_fg: Option<String>,
 }
 
 impl<'de> Deserialize<'de> for Theme {
@@ -218,6 +230,20 @@ impl<'de> Deserialize<'de> for Theme {
                         &inner.dimmed_text_fg
                     ))
                 })?,
+            // this is optional for theme backwards compatibility and falls back to red
+            error_fg: match inner.error_fg {
+                Some(s) => Color::from_str(&s).ok_or_else(|| {
+                    serde::de::Error::custom(format!("invalid color {}", &s))
+                })?,
+                None => Color::Ansi(ANSIColor::Red),
+            },
+            // this is optional for theme backwards compatibility and falls back to yellow
+            warning_fg: match inner.warning_fg {
+                Some(s) => Color::from_str(&s).ok_or_else(|| {
+                    serde::de::Error::custom(format!("invalid color {}", &s))
+                })?,
+                None => Color::Ansi(ANSIColor::Yellow),
+            },
             input_text_fg: Color::from_str(&inner.input_text_fg).ok_or_else(
                 || {
                     serde::de::Error::custom(format!(
@@ -306,6 +332,18 @@ impl<'de> Deserialize<'de> for Theme {
                     &inner.remote_control_mode_fg
                 ))
             })?,
+            send_to_channel_mode_fg: match inner.send_to_channel_mode_fg {
+                Some(s) => Color::from_str(&s).ok_or_else(|| {
+                    serde::de::Error::custom(format!("invalid color {}", &s))
+                })?,
+                None => Color::from_str(&inner.remote_control_mode_fg)
+                    .ok_or_else(|| {
+                        serde::de::Error::custom(format!(
+                            "invalid color {}",
+                            &inner.remote_control_mode_fg
+                        ))
+                    })?,
+            },
         })
     }
 }
@@ -371,6 +409,8 @@ impl Into<GeneralColorscheme> for &Theme {
         GeneralColorscheme {
             background: self.background.as_ref().map(Into::into),
             border_fg: (&self.border_fg).into(),
+            error_fg: (&self.error_fg).into(),
+            warning_fg: (&self.warning_fg).into(),
         }
     }
 }
@@ -428,6 +468,7 @@ impl Into<ModeColorscheme> for &Theme {
         ModeColorscheme {
             channel: (&self.channel_mode_fg).into(),
             remote_control: (&self.remote_control_mode_fg).into(),
+            send_to_channel: (&self.send_to_channel_mode_fg).into(),
         }
     }
 }
@@ -443,6 +484,8 @@ mod tests {
             border_fg = "black"
             text_fg = "white"
             dimmed_text_fg = "bright-black"
+            error_fg = "bright-red"
+            warning_fg = "bright-yellow"
             input_text_fg = "bright-white"
             result_count_fg = "bright-white"
             result_name_fg = "bright-white"
@@ -454,6 +497,7 @@ mod tests {
             preview_title_fg = "bright-white"
             channel_mode_fg = "bright-white"
             remote_control_mode_fg = "bright-white"
+            send_to_channel_mode_fg = "cyan"
         "##;
         let theme: Theme = toml::from_str(theme_content).unwrap();
         assert_eq!(
@@ -463,6 +507,8 @@ mod tests {
         assert_eq!(theme.border_fg, Color::Ansi(ANSIColor::Black));
         assert_eq!(theme.text_fg, Color::Ansi(ANSIColor::White));
         assert_eq!(theme.dimmed_text_fg, Color::Ansi(ANSIColor::BrightBlack));
+        assert_eq!(theme.error_fg, Color::Ansi(ANSIColor::BrightRed));
+        assert_eq!(theme.warning_fg, Color::Ansi(ANSIColor::BrightYellow));
         assert_eq!(theme.input_text_fg, Color::Ansi(ANSIColor::BrightWhite));
         assert_eq!(theme.result_count_fg, Color::Ansi(ANSIColor::BrightWhite));
         assert_eq!(theme.result_name_fg, Color::Ansi(ANSIColor::BrightWhite));
@@ -483,6 +529,10 @@ mod tests {
             theme.remote_control_mode_fg,
             Color::Ansi(ANSIColor::BrightWhite)
         );
+        assert_eq!(
+            theme.send_to_channel_mode_fg,
+            Color::Ansi(ANSIColor::Cyan)
+        );
     }
 
     #[test]
@@ -508,6 +558,8 @@ mod tests {
         assert_eq!(theme.border_fg, Color::Ansi(ANSIColor::Black));
         assert_eq!(theme.text_fg, Color::Ansi(ANSIColor::White));
         assert_eq!(theme.dimmed_text_fg, Color::Ansi(ANSIColor::BrightBlack));
+        assert_eq!(theme.error_fg, Color::Ansi(ANSIColor::Red));
+        assert_eq!(theme.warning_fg, Color::Ansi(ANSIColor::Yellow));
         assert_eq!(theme.input_text_fg, Color::Ansi(ANSIColor::BrightWhite));
         assert_eq!(
             theme.result_count_fg,
@@ -531,5 +583,9 @@ mod tests {
             theme.remote_control_mode_fg,
             Color::Ansi(ANSIColor::BrightWhite)
         );
+        assert_eq!(
+            theme.send_to_channel_mode_fg,
+            Color::Ansi(ANSIColor::BrightWhite)
+        );
     }
 }
@@ -6,18 +6,23 @@ use rustc_hash::FxHashSet;
 
 use crate::{
     action::Action,
-    channels::entry::Entry,
+    channels::{cable::SourceError, entry::Entry},
     config::Config,
     picker::Picker,
     previewer::state::PreviewState,
     screen::{
-        colors::Colorscheme, help::draw_help_bar, input::draw_input_box,
-        keybindings::build_keybindings_table, layout::Layout,
+        colors::Colorscheme,
+        help::draw_help_bar,
+        input::draw_input_box,
+        keybindings::build_keybindings_table,
+        layout::Layout,
+        notifications::{draw_notifications, Notifications},
         preview::draw_preview_content_block,
-        remote_control::draw_remote_control, results::draw_results_list,
+        remote_control::draw_remote_control,
+        results::draw_results_list,
         spinner::Spinner,
     },
-    television::Mode,
+    television::{MatchingMode, Mode},
     utils::metadata::AppMetadata,
 };
 
@@ -30,6 +35,13 @@ pub struct ChannelState {
     pub selected_entries: FxHashSet<Entry>,
     pub total_count: u32,
     pub running: bool,
+    /// The channel's custom actions along with their keys.
+    pub actions: Vec<(String, String)>,
+    pub matching_mode: MatchingMode,
+    /// Whether the current pattern is invalid in the current matching mode.
+    pub invalid_pattern: bool,
+    /// How the channel's source command failed, if it did.
+    pub source_error: Option<SourceError>,
 }
 
 impl ChannelState {
@@ -44,8 +56,35 @@ impl ChannelState {
             selected_entries,
             total_count,
             running,
+            actions: Vec::new(),
+            matching_mode: MatchingMode::Fuzzy,
+            invalid_pattern: false,
+            source_error: None,
         }
     }
+
+    pub fn with_actions(mut self, actions: Vec<(String, String)>) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    pub fn with_matching_mode(
+        mut self,
+        matching_mode: MatchingMode,
+        invalid_pattern: bool,
+    ) -> Self {
+        self.matching_mode = matching_mode;
+        self.invalid_pattern = invalid_pattern;
+        self
+    }
+
+    pub fn with_source_error(
+        mut self,
+        source_error: Option<SourceError>,
+    ) -> Self {
+        self.source_error = source_error;
+        self
+    }
 }
 
 impl Hash for ChannelState {
@@ -56,6 +95,10 @@ impl Hash for ChannelState {
             .for_each(|entry| entry.hash(state));
         self.total_count.hash(state);
         self.running.hash(state);
+        self.actions.hash(state);
+        self.matching_mode.hash(state);
+        self.invalid_pattern.hash(state);
+        self.source_error.hash(state);
     }
 }
 
@@ -71,6 +114,7 @@ pub struct TvState {
     pub channel_state: ChannelState,
     pub spinner: Spinner,
     pub preview_state: PreviewState,
+    pub notifications: Notifications,
 }
 
 impl TvState {
@@ -92,8 +136,14 @@ impl TvState {
             channel_state,
             spinner,
             preview_state,
+            notifications: Notifications::default(),
         }
     }
+
+    pub fn with_notifications(mut self, notifications: Notifications) -> Self {
+        self.notifications = notifications;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -195,6 +245,7 @@ pub fn draw(ctx: &Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
         &ctx.tv_state.channel_state.current_channel_name,
         build_keybindings_table(
             &ctx.config.keybindings.to_displayable(),
+            &ctx.tv_state.channel_state.actions,
             ctx.tv_state.mode,
             &ctx.colorscheme,
         ),
@@ -228,6 +279,7 @@ pub fn draw(ctx: &Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
         // only show the preview keybinding hint if there's actually something to preview
         ctx.tv_state.preview_state.enabled,
         ctx.config.ui.no_help,
+        ctx.tv_state.channel_state.source_error.as_ref(),
     )?;
 
     // input box
@@ -239,6 +291,8 @@ pub fn draw(ctx: &Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
         &ctx.tv_state.results_picker.input,
         &ctx.tv_state.results_picker.state,
         ctx.tv_state.channel_state.running,
+        ctx.tv_state.channel_state.matching_mode,
+        ctx.tv_state.channel_state.invalid_pattern,
         &ctx.tv_state.channel_state.current_channel_name,
         &ctx.tv_state.spinner,
         &ctx.colorscheme,
@@ -270,5 +324,8 @@ pub fn draw(ctx: &Ctx, f: &mut Frame<'_>, area: Rect) -> Result<Layout> {
         )?;
     }
 
+    // notifications are drawn on top of everything else
+    draw_notifications(f, area, &ctx.tv_state.notifications, &ctx.colorscheme);
+
     Ok(layout)
 }
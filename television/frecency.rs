@@ -0,0 +1,224 @@
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use rustc_hash::FxHashMap;
+use tracing::debug;
+
+use crate::history::file_name;
+
+/// The directory (inside the data directory) where frecency records are
+/// stored, one file per channel.
+const FRECENCY_DIR: &str = "frecency";
+
+/// The maximum number of entries remembered for each channel.
+///
+/// Once this is reached, the entries with the lowest score are dropped.
+const MAX_ENTRIES: usize = 1000;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+/// How often and how recently an entry was picked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Record {
+    count: u32,
+    /// When the entry was last picked, in seconds since the unix epoch.
+    last_used: u64,
+}
+
+impl Record {
+    /// The entry's frecency at the given time: the number of times it was
+    /// picked, weighted by how recently it was last picked.
+    fn score(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last_used);
+        let weight = if age < HOUR {
+            4.0
+        } else if age < DAY {
+            2.0
+        } else if age < WEEK {
+            0.5
+        } else {
+            0.25
+        };
+        f64::from(self.count) * weight
+    }
+}
+
+/// The entries picked in a channel, ranked by frecency (frequency and
+/// recency) and persisted across sessions.
+///
+/// Records are stored in `<data_dir>/frecency/<channel>`, one per line as
+/// `<count>\t<last used>\t<entry>`.
+///
+/// # Example
+/// ```ignore
+/// use television::frecency::Frecency;
+///
+/// let mut frecency = Frecency::load(data_dir, "files");
+/// frecency.record(&["README.md"])?;
+///
+/// assert!(frecency.score("README.md").is_some());
+/// assert!(frecency.score("Cargo.toml").is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Frecency {
+    path: PathBuf,
+    records: FxHashMap<String, Record>,
+}
+
+impl Frecency {
+    /// Load the frecency records of the given channel.
+    pub fn load(data_dir: &Path, channel: &str) -> Self {
+        let path = data_dir.join(FRECENCY_DIR).join(file_name(channel));
+        let records = read(&path);
+        Self { path, records }
+    }
+
+    /// The frecency of the given entry, if it was ever picked.
+    pub fn score(&self, entry: &str) -> Option<f64> {
+        self.records.get(entry).map(|r| r.score(now()))
+    }
+
+    /// The frecency of every recorded entry, indexed by the entry's bytes.
+    pub fn scores(&self) -> FxHashMap<Vec<u8>, f64> {
+        let now = now();
+        self.records
+            .iter()
+            .map(|(entry, r)| (entry.as_bytes().to_vec(), r.score(now)))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Record that the given entries were picked and save the records.
+    ///
+    /// Entries spanning multiple lines can't be recorded and are ignored.
+    pub fn record(&mut self, entries: &[&str]) -> Result<()> {
+        let now = now();
+        self.update(|records| {
+            for entry in entries {
+                if entry.is_empty() || entry.contains('\n') {
+                    continue;
+                }
+                let record =
+                    records.entry((*entry).to_string()).or_insert(Record {
+                        count: 0,
+                        last_used: now,
+                    });
+                record.count = record.count.saturating_add(1);
+                record.last_used = now;
+            }
+        })
+    }
+
+    /// Forget everything about the given entry and save the records.
+    pub fn forget(&mut self, entry: &str) -> Result<()> {
+        self.update(|records| {
+            records.remove(entry);
+        })
+    }
+
+    /// Apply the given change to the records and save them.
+    ///
+    /// The records are read again beforehand so that changes made in the
+    /// meantime (e.g. by another session) aren't lost.
+    fn update<F>(&mut self, change: F) -> Result<()>
+    where
+        F: FnOnce(&mut FxHashMap<String, Record>),
+    {
+        let mut records = read(&self.path);
+        change(&mut records);
+        if records.len() > MAX_ENTRIES {
+            let now = now();
+            let mut ranked = records.into_iter().collect::<Vec<_>>();
+            ranked.sort_by(|a, b| b.1.score(now).total_cmp(&a.1.score(now)));
+            ranked.truncate(MAX_ENTRIES);
+            records = ranked.into_iter().collect();
+        }
+        self.records = records;
+
+        debug!("Saving frecency records to {:?}", self.path);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for (entry, r) in &self.records {
+            writeln!(contents, "{}\t{}\t{}", r.count, r.last_used, entry)?;
+        }
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+fn read(path: &Path) -> FxHashMap<String, Record> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return FxHashMap::default();
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let count = parts.next()?.parse().ok()?;
+            let last_used = parts.next()?.parse().ok()?;
+            let entry = parts.next()?;
+            Some((entry.to_string(), Record { count, last_used }))
+        })
+        .collect()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_persists_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut frecency = Frecency::load(dir.path(), "files");
+        frecency.record(&["a", "b\tc"]).unwrap();
+        frecency.record(&["a", "multi\nline"]).unwrap();
+
+        let frecency = Frecency::load(dir.path(), "files");
+        assert_eq!(frecency.score("a"), Some(8.0));
+        assert_eq!(frecency.score("b\tc"), Some(4.0));
+        assert_eq!(frecency.score("multi\nline"), None);
+        assert!(Frecency::load(dir.path(), "dirs").is_empty());
+    }
+
+    #[test]
+    fn test_forget() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut frecency = Frecency::load(dir.path(), "files");
+        frecency.record(&["a", "b"]).unwrap();
+        frecency.forget("a").unwrap();
+
+        let frecency = Frecency::load(dir.path(), "files");
+        assert_eq!(frecency.score("a"), None);
+        assert!(frecency.score("b").is_some());
+    }
+
+    #[test]
+    fn test_score_decays_with_age() {
+        let record = Record {
+            count: 2,
+            last_used: 0,
+        };
+        assert_eq!(record.score(10), 8.0);
+        assert_eq!(record.score(2 * HOUR), 4.0);
+        assert_eq!(record.score(2 * DAY), 1.0);
+        assert_eq!(record.score(2 * WEEK), 0.5);
+    }
+}
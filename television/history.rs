@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use tracing::debug;
+
+/// The directory (inside the data directory) where query histories are
+/// stored, one file per channel.
+const HISTORY_DIR: &str = "history";
+
+/// The queries submitted in a channel, persisted across sessions.
+///
+/// Queries are stored in `<data_dir>/history/<channel>`, one per line and
+/// from the oldest to the most recent one. A query that is submitted again
+/// is moved to the end of the history rather than recorded twice.
+///
+/// # Example
+/// ```ignore
+/// use television::history::QueryHistory;
+///
+/// let mut history = QueryHistory::load(data_dir, "files", 100);
+/// history.add("foo")?;
+/// history.add("bar")?;
+///
+/// assert_eq!(history.previous_query(""), Some("bar"));
+/// assert_eq!(history.previous_query("bar"), Some("foo"));
+/// assert_eq!(history.next_query(), Some("bar"));
+/// // back to what was being typed before browsing the history
+/// assert_eq!(history.next_query(), Some(""));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QueryHistory {
+    path: PathBuf,
+    /// The maximum number of queries to keep, `0` disables the history.
+    max_size: usize,
+    queries: Vec<String>,
+    /// The index of the query currently being shown when browsing the
+    /// history.
+    cursor: Option<usize>,
+    /// What was in the input before browsing the history.
+    draft: String,
+}
+
+impl QueryHistory {
+    /// Load the query history of the given channel.
+    pub fn load(data_dir: &Path, channel: &str, max_size: usize) -> Self {
+        let path = data_dir.join(HISTORY_DIR).join(file_name(channel));
+        let mut history = Self {
+            path,
+            max_size,
+            ..Default::default()
+        };
+        history.queries = history.read();
+        history
+    }
+
+    pub fn queries(&self) -> &[String] {
+        &self.queries
+    }
+
+    /// Record a submitted query and save the history.
+    ///
+    /// The history is read again beforehand so that queries recorded in
+    /// the meantime (e.g. by another session) aren't lost.
+    pub fn add(&mut self, query: &str) -> Result<()> {
+        self.reset();
+        if self.max_size == 0 || query.trim().is_empty() {
+            return Ok(());
+        }
+        let mut queries = self.read();
+        queries.retain(|q| q != query);
+        queries.push(query.to_string());
+        let excess = queries.len().saturating_sub(self.max_size);
+        queries.drain(..excess);
+        self.queries = queries;
+
+        debug!("Saving query history to {:?}", self.path);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = self.queries.join("\n");
+        contents.push('\n');
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+
+    /// Move to the previous (older) query, `current` being what is in the
+    /// input right now.
+    ///
+    /// Returns `None` if there is no older query.
+    pub fn previous_query(&mut self, current: &str) -> Option<&str> {
+        let index = match self.cursor {
+            None => {
+                self.draft = current.to_string();
+                self.queries.len().checked_sub(1)?
+            }
+            Some(index) => index.checked_sub(1)?,
+        };
+        self.cursor = Some(index);
+        Some(&self.queries[index])
+    }
+
+    /// Move to the next (more recent) query.
+    ///
+    /// Moving past the most recent query brings back what was in the input
+    /// before browsing the history. Returns `None` if the history isn't
+    /// being browsed.
+    pub fn next_query(&mut self) -> Option<&str> {
+        let index = self.cursor? + 1;
+        if index < self.queries.len() {
+            self.cursor = Some(index);
+            Some(&self.queries[index])
+        } else {
+            self.cursor = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// Stop browsing the history.
+    pub fn reset(&mut self) {
+        self.cursor = None;
+    }
+
+    fn read(&self) -> Vec<String> {
+        std::fs::read_to_string(&self.path)
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter(|q| !q.trim().is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// A file name for the given channel that can't escape the directory it is
+/// stored in.
+pub(crate) fn file_name(channel: &str) -> String {
+    channel
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_persists_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = QueryHistory::load(dir.path(), "files", 10);
+        history.add("foo").unwrap();
+        history.add("bar").unwrap();
+        history.add(" ").unwrap();
+
+        let history = QueryHistory::load(dir.path(), "files", 10);
+        assert_eq!(history.queries(), ["foo", "bar"]);
+        assert!(QueryHistory::load(dir.path(), "text", 10)
+            .queries()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_add_deduplicates_and_truncates() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = QueryHistory::load(dir.path(), "files", 3);
+        for query in ["a", "b", "c", "a", "d"] {
+            history.add(query).unwrap();
+        }
+        assert_eq!(history.queries(), ["c", "a", "d"]);
+    }
+
+    #[test]
+    fn test_add_keeps_queries_from_other_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut first = QueryHistory::load(dir.path(), "files", 10);
+        let mut second = QueryHistory::load(dir.path(), "files", 10);
+        first.add("foo").unwrap();
+        second.add("bar").unwrap();
+        assert_eq!(second.queries(), ["foo", "bar"]);
+    }
+
+    #[test]
+    fn test_disabled_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = QueryHistory::load(dir.path(), "files", 0);
+        history.add("foo").unwrap();
+        assert!(history.queries().is_empty());
+        assert!(!dir.path().join(HISTORY_DIR).exists());
+    }
+
+    #[test]
+    fn test_browse_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = QueryHistory::load(dir.path(), "files", 10);
+        assert_eq!(history.previous_query("draft"), None);
+        history.add("foo").unwrap();
+        history.add("bar").unwrap();
+
+        assert_eq!(history.next_query(), None);
+        assert_eq!(history.previous_query("draft"), Some("bar"));
+        assert_eq!(history.previous_query("bar"), Some("foo"));
+        assert_eq!(history.previous_query("foo"), None);
+        assert_eq!(history.next_query(), Some("bar"));
+        assert_eq!(history.next_query(), Some("draft"));
+        assert_eq!(history.next_query(), None);
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("git-log"), "git-log");
+        assert_eq!(file_name("../my channel"), "___my_channel");
+    }
+}
@@ -7,6 +7,8 @@ pub mod config;
 pub mod draw;
 pub mod errors;
 pub mod event;
+pub mod frecency;
+pub mod history;
 pub mod input;
 pub mod keymap;
 pub mod logging;
@@ -5,9 +5,10 @@ use std::process::exit;
 
 use anyhow::Result;
 use clap::Parser;
-use television::cable::load_cable;
+use television::cable::{load_cable_with_errors, shadowed_keybindings};
 use television::cli::post_process;
 use television::{
+    action::Action,
     channels::prototypes::{Cable, ChannelPrototype},
     utils::clipboard::CLIPBOARD,
 };
@@ -20,8 +21,10 @@ use television::cli::{
 };
 
 use television::config::{merge_keybindings, Config, ConfigEnv};
+use television::keymap::Keymap;
 use television::utils::shell::render_autocomplete_script_template;
 use television::utils::{
+    command::foreground_shell_command,
     shell::{completion_script, Shell},
     stdin::is_readable_stdin,
 };
@@ -42,7 +45,8 @@ async fn main() -> Result<()> {
     let mut config = Config::new(&ConfigEnv::init()?)?;
 
     debug!("Loading cable channels...");
-    let cable = load_cable().unwrap_or_default();
+    let (cable, mut cable_errors) =
+        load_cable_with_errors().unwrap_or_default();
 
     let args = post_process(cli, &cable);
     debug!("PostProcessedCli: {:?}", args);
@@ -59,6 +63,10 @@ async fn main() -> Result<()> {
     // optionally override configuration values with CLI arguments
     debug!("Applying CLI overrides...");
     apply_cli_overrides(&args, &mut config);
+    cable_errors.extend(shadowed_keybindings(
+        &cable,
+        &Keymap::from(&config.keybindings),
+    ));
 
     // determine the channel to use based on the CLI arguments and configuration
     debug!("Determining channel...");
@@ -77,6 +85,10 @@ async fn main() -> Result<()> {
     );
     let mut app =
         App::new(&channel_prototype, config, args.input, options, &cable);
+    // report broken cable files and shadowed keybindings once the UI is up
+    for error in cable_errors {
+        app.action_tx.send(Action::Error(error))?;
+    }
     stdout().flush()?;
     debug!("Running application...");
     let output = app.run(stdout().is_terminal(), false).await?;
@@ -85,13 +97,35 @@ async fn main() -> Result<()> {
     let mut bufwriter = BufWriter::new(stdout_handle);
     if let Some(entries) = output.selected_entries {
         for entry in &entries {
-            writeln!(bufwriter, "{}", entry.stdout_repr())?;
+            bufwriter.write_all(&entry.stdout_bytes())?;
+            bufwriter.write_all(if args.print0 { b"\0" } else { b"\n" })?;
         }
     }
     bufwriter.flush()?;
+    if let Some(command) = output.become_command {
+        become_command(&command)?;
+    }
     exit(0);
 }
 
+/// Replace the current process with the given command.
+#[cfg(unix)]
+fn become_command(command: &str) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    debug!("Becoming {:?}", command);
+    // `exec` only returns if something went wrong
+    Err(foreground_shell_command(false).arg(command).exec().into())
+}
+
+/// Run the given command and exit with its status code.
+#[cfg(not(unix))]
+fn become_command(command: &str) -> Result<()> {
+    debug!("Becoming {:?}", command);
+    let status = foreground_shell_command(false).arg(command).status()?;
+    exit(status.code().unwrap_or(1));
+}
+
 /// Apply overrides from the CLI arguments to the configuration.
 ///
 /// This function mutates the configuration in place.
@@ -109,6 +143,9 @@ fn apply_cli_overrides(args: &PostProcessedCli, config: &mut Config) {
     if let Some(header) = &args.custom_header {
         config.ui.custom_header = Some(header.to_string());
     }
+    if let Some(threads) = args.threads {
+        config.matcher.n_threads = Some(threads);
+    }
     config.ui.ui_scale = args.ui_scale;
 }
 
@@ -154,7 +191,7 @@ pub fn determine_channel(
     readable_stdin: bool,
     cable: &Cable,
 ) -> ChannelPrototype {
-    if readable_stdin {
+    let prototype = if readable_stdin {
         debug!("Using stdin channel");
         ChannelPrototype::stdin(args.preview_command.clone())
     } else if let Some(prompt) = &args.autocomplete_prompt {
@@ -181,7 +218,17 @@ pub fn determine_channel(
         }
 
         prototype
-    }
+    };
+
+    // cli flags only ever turn these on, on top of the channel's settings
+    let read0 = prototype.read0 || args.read0;
+    let ansi = prototype.ansi || args.ansi;
+    let sort = if args.no_sort {
+        Some(false)
+    } else {
+        prototype.sort
+    };
+    prototype.with_read0(read0).with_ansi(ansi).with_sort(sort)
 }
 
 #[cfg(test)]
@@ -322,17 +369,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_determine_channel_read0() {
+        let args = PostProcessedCli {
+            read0: true,
+            ..Default::default()
+        };
+        let config = Config::default();
+
+        let channel =
+            determine_channel(&args, &config, true, &Cable::default());
+        assert!(channel.read0);
+
+        let channel = determine_channel(
+            &PostProcessedCli {
+                channel: Some(String::from("dirs")),
+                ..args
+            },
+            &config,
+            false,
+            &Cable::default(),
+        );
+        assert!(channel.read0);
+    }
+
+    #[test]
+    fn test_determine_channel_ansi() {
+        let args = PostProcessedCli {
+            channel: Some(String::from("files")),
+            ansi: true,
+            ..Default::default()
+        };
+        let config = Config::default();
+
+        let channel =
+            determine_channel(&args, &config, false, &Cable::default());
+        assert!(channel.ansi);
+
+        let args = PostProcessedCli {
+            channel: Some(String::from("files")),
+            ..Default::default()
+        };
+        let channel =
+            determine_channel(&args, &config, false, &Cable::default());
+        assert!(!channel.ansi);
+    }
+
+    #[test]
+    fn test_determine_channel_no_sort() {
+        let args = PostProcessedCli {
+            channel: Some(String::from("dirs")),
+            no_sort: true,
+            ..Default::default()
+        };
+        let config = Config::default();
+
+        let channel =
+            determine_channel(&args, &config, false, &Cable::default());
+        assert_eq!(channel.sort, Some(false));
+        assert!(!channel.matcher_config(config.matcher).sort);
+    }
+
     #[test]
     fn test_apply_cli_overrides() {
         let mut config = Config::default();
         let args = PostProcessedCli {
             tick_rate: Some(100_f64),
             no_preview: true,
+            threads: Some(2),
             ..Default::default()
         };
         apply_cli_overrides(&args, &mut config);
 
         assert_eq!(config.application.tick_rate, 100_f64);
         assert!(!config.ui.show_preview_panel);
+        assert_eq!(config.matcher.n_threads, Some(2));
     }
 }
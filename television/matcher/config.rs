@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// The configuration of the fuzzy matcher.
 ///
 /// This contains the number of threads to use, whether to ignore case, whether
@@ -8,10 +10,15 @@
 /// matches, and no optimization for matching paths as well as using the
 /// default number of threads (which corresponds to the number of available logical
 /// cores on the current machine).
-#[derive(Copy, Clone, Debug)]
+///
+/// This is the `[matcher]` section of the configuration file, which cable
+/// channels can override (see `ChannelPrototype`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct Config {
     /// The number of threads to use for the fuzzy matcher.
+    #[serde(rename = "threads")]
     pub n_threads: Option<usize>,
     /// Whether to ignore case when matching.
     pub ignore_case: bool,
@@ -19,8 +26,29 @@ pub struct Config {
     pub prefer_prefix: bool,
     /// Whether to optimize for matching paths.
     pub match_paths: bool,
+    /// Whether to rank matches by score, or keep them in the order they
+    /// were pushed in.
+    pub sort: bool,
+    /// How matches with the same score are ordered, by order of priority.
+    pub tiebreak: Vec<TieBreak>,
+}
+
+/// A criterion used to order matches with the same score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TieBreak {
+    /// Items pushed first come first.
+    Index,
+    /// Shorter items come first.
+    Length,
+    /// Items with fewer path separators come first.
+    PathDepth,
 }
 
+/// How nucleo orders matches with the same score on its own.
+pub const DEFAULT_TIEBREAK: [TieBreak; 2] =
+    [TieBreak::Length, TieBreak::Index];
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -28,6 +56,8 @@ impl Default for Config {
             ignore_case: true,
             prefer_prefix: false,
             match_paths: false,
+            sort: true,
+            tiebreak: DEFAULT_TIEBREAK.to_vec(),
         }
     }
 }
@@ -56,6 +86,18 @@ impl Config {
         self.match_paths = match_paths;
         self
     }
+
+    /// Set whether to rank matches by score.
+    pub fn sort(mut self, sort: bool) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Set how matches with the same score are ordered.
+    pub fn tiebreak(mut self, tiebreak: Vec<TieBreak>) -> Self {
+        self.tiebreak = tiebreak;
+        self
+    }
 }
 
 impl From<&Config> for nucleo::Config {
@@ -1,3 +1,10 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use super::Indexed;
+
 /// An injector that can be used to push items of type `I` into the fuzzy matcher.
 ///
 /// This is a wrapper around the `Injector` type from the `Nucleo` fuzzy matcher.
@@ -10,15 +17,38 @@ where
     I: Sync + Send + Clone + 'static,
 {
     /// The inner `Injector` from the `Nucleo` fuzzy matcher.
-    inner: nucleo::Injector<I>,
+    inner: nucleo::Injector<Indexed<I>>,
+    /// The number of items pushed into the matcher so far, shared by all
+    /// the injectors of the matcher.
+    count: Arc<AtomicU32>,
+    /// The number of items the matcher has taken in as of its last tick.
+    processed: Arc<AtomicU32>,
 }
 
 impl<I> Injector<I>
 where
     I: Sync + Send + Clone + 'static,
 {
-    pub fn new(inner: nucleo::Injector<I>) -> Self {
-        Self { inner }
+    pub(crate) fn new(
+        inner: nucleo::Injector<Indexed<I>>,
+        count: Arc<AtomicU32>,
+        processed: Arc<AtomicU32>,
+    ) -> Self {
+        Self {
+            inner,
+            count,
+            processed,
+        }
+    }
+
+    /// The number of items pushed into the matcher that it hasn't taken in
+    /// yet, e.g. to stop pushing more while it's catching up.
+    ///
+    /// Items are only taken in when the matcher is ticked.
+    pub fn pending_count(&self) -> u32 {
+        self.count
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.processed.load(Ordering::Relaxed))
     }
 
     /// Push an item into the fuzzy matcher.
@@ -44,6 +74,9 @@ where
     where
         F: FnOnce(&I, &mut [nucleo::Utf32String]),
     {
-        self.inner.push(item, f);
+        let index = self.count.fetch_add(1, Ordering::Relaxed);
+        self.inner.push(Indexed { index, item }, |indexed, cols| {
+            f(&indexed.item, cols);
+        });
     }
 }
@@ -17,4 +17,7 @@ where
     pub matched_string: String,
     /// The indices of the matched characters.
     pub match_indices: Vec<u32>,
+    /// The indices of the matched characters in each of the matcher's named
+    /// columns, in order (see `Matcher::with_columns`).
+    pub column_indices: Vec<Vec<u32>>,
 }
@@ -1,10 +1,22 @@
 use injector::Injector;
-use std::sync::Arc;
+use nucleo::pattern::{CaseMatching, Normalization};
+use parking_lot::Mutex;
+use promotion::{PromotionFn, PromotionMode, Promotions};
+use regex::Regex;
+use regex_filter::RegexFilter;
+use rustc_hash::FxHashMap;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
 
 pub mod config;
 pub mod injector;
 pub mod lazy;
 pub mod matched_item;
+mod order;
+pub mod promotion;
+mod regex_filter;
 
 const MATCHER_TICK_TIMEOUT: u64 = 2;
 
@@ -28,20 +40,32 @@ impl From<nucleo::Status> for Status {
     }
 }
 
+/// An item pushed into the matcher along with the order it was pushed in.
+pub(crate) struct Indexed<I> {
+    index: u32,
+    item: I,
+}
+
 /// A fuzzy matcher that can be used to match items of type `I`.
 ///
 /// `I` should be `Sync`, `Send`, `Clone`, and `'static`.
-/// This is a wrapper around the `Nucleo` fuzzy matcher that only matches
-/// on a single dimension.
+/// This is a wrapper around the `Nucleo` fuzzy matcher that matches on a
+/// main dimension and, optionally, on named columns (see `with_columns`).
 ///
 /// The matcher can be used to find items that match a given pattern and to
 /// retrieve the matched items as well as the indices of the matched characters.
+#[allow(clippy::struct_excessive_bools)]
 pub struct Matcher<I>
 where
     I: Sync + Send + Clone + 'static,
 {
     /// The inner `Nucleo` fuzzy matcher.
-    inner: nucleo::Nucleo<I>,
+    inner: nucleo::Nucleo<Indexed<I>>,
+    /// The number of items pushed into the matcher so far (see `Injector`).
+    pushed_item_count: Arc<AtomicU32>,
+    /// The number of items the matcher has taken in as of the last tick
+    /// (see `Injector::pending_count`).
+    processed_item_count: Arc<AtomicU32>,
     /// The current total number of items in the matcher.
     pub total_item_count: u32,
     /// The current number of matched items in the matcher.
@@ -50,6 +74,45 @@ where
     pub status: Status,
     /// The last pattern that was matched against.
     pub last_pattern: String,
+    /// The names of the matcher's columns, on top of the main one.
+    columns: Vec<String>,
+    /// The last pattern each column was matched against (see
+    /// `column_patterns`).
+    last_column_patterns: Vec<String>,
+    /// Used to move some items around in the results (see `set_promotion`).
+    promotion: Option<(PromotionFn<I>, PromotionMode)>,
+    /// The items that should be promoted, by index (see `Indexed`), along
+    /// with their index in nucleo's items and their score.
+    promoted: FxHashMap<u32, (u32, f64)>,
+    /// The number of nucleo's items checked for promotion so far.
+    promotions_scanned: u32,
+    promotions: Promotions,
+    /// Whether to rank matches by score (see `Config::sort`).
+    sort: bool,
+    /// How matches with the same score are ordered (see `Config::tiebreak`).
+    tiebreak: Vec<config::TieBreak>,
+    /// The order of the first matches, as positions in nucleo's matches,
+    /// when it differs from nucleo's ranking (see `order_window`).
+    order: Option<Vec<u32>>,
+    /// How many matches the order is worked out for, which grows with the
+    /// results that were asked for.
+    order_window: u32,
+    /// Scores matches the same way nucleo does, to order the ones with the
+    /// same score.
+    scorer: Mutex<nucleo::Matcher>,
+    /// What the order and promotions were last worked out from, if the
+    /// matches were final then (see `order::Key`).
+    order_key: Option<order::Key>,
+    /// Whether the order and promotions need to be worked out again.
+    ranking_outdated: bool,
+    /// Whether patterns are matched case-sensitively (see
+    /// `set_case_sensitive`).
+    case_sensitive: bool,
+    /// Whether case is ignored when the pattern is all lowercase.
+    ignore_case: bool,
+    /// Used instead of fuzzy matching when filtering with a regex (see
+    /// `find_regex`).
+    regex: Option<RegexFilter>,
 }
 
 impl<I> Matcher<I>
@@ -58,17 +121,49 @@ where
 {
     /// Create a new fuzzy matcher with the given configuration.
     pub fn new(config: config::Config) -> Self {
+        Self::with_columns(config, Vec::new())
+    }
+
+    /// Create a new fuzzy matcher with the given configuration that also
+    /// matches items on the given named columns.
+    ///
+    /// Items are pushed with their main dimension in `cols[0]` and their
+    /// columns in the following ones, in order. Parts of the pattern
+    /// prefixed with a column's name (e.g. `path:src`) are then matched
+    /// against that column only, while the rest of the pattern is matched
+    /// against the main dimension.
+    pub fn with_columns(config: config::Config, columns: Vec<String>) -> Self {
+        let column_count = columns.len() + 1;
+        let scorer = Mutex::new(nucleo::Matcher::new((&config).into()));
         Self {
             inner: nucleo::Nucleo::new(
                 (&config).into(),
                 Arc::new(|| {}),
                 config.n_threads,
-                1,
+                u32::try_from(column_count).unwrap(),
             ),
+            pushed_item_count: Arc::new(AtomicU32::new(0)),
+            processed_item_count: Arc::new(AtomicU32::new(0)),
             total_item_count: 0,
             matched_item_count: 0,
             status: Status::default(),
             last_pattern: String::new(),
+            columns,
+            last_column_patterns: vec![String::new(); column_count],
+            promotion: None,
+            promoted: FxHashMap::default(),
+            promotions_scanned: 0,
+            promotions: Promotions::default(),
+            sort: config.sort,
+            tiebreak: config.tiebreak,
+            order: None,
+            order_window: order::MIN_WINDOW,
+            scorer,
+            order_key: None,
+            ranking_outdated: false,
+            case_sensitive: false,
+            ignore_case: config.ignore_case,
+            regex: None,
         }
     }
 
@@ -76,7 +171,185 @@ where
     ///
     /// This should be called periodically to update the state of the matcher.
     pub fn tick(&mut self) {
-        self.status = self.inner.tick(MATCHER_TICK_TIMEOUT).into();
+        let status = self.inner.tick(MATCHER_TICK_TIMEOUT);
+        self.status = status.into();
+        self.processed_item_count
+            .store(self.inner.snapshot().item_count(), Ordering::Relaxed);
+        let mut changed = status.changed;
+        if let Some(regex) = &mut self.regex {
+            let snapshot = self.inner.snapshot();
+            changed |= regex.scan(snapshot);
+            self.status.running |= !regex.is_done(snapshot);
+        }
+        self.ranking_outdated |= self.scan_promotions();
+        if changed || self.ranking_outdated {
+            let key =
+                (self.regex.is_none() && !self.status.running).then(|| {
+                    order::Key::new(self.inner.snapshot(), &self.last_pattern)
+                });
+            // ticks may report changes while the matches stay the same, in
+            // which case there's no need to go through all of them again
+            if self.ranking_outdated || key.is_none() || key != self.order_key
+            {
+                self.ranking_outdated = false;
+                // promoted items may be anywhere in the results
+                let window = if self.promoted.is_empty() {
+                    self.order_window
+                } else {
+                    u32::MAX
+                };
+                self.order = self.order(window);
+                self.update_promotions();
+            }
+            self.order_key = key;
+        }
+    }
+
+    /// Promote some items in the results, on top of the fuzzy matching
+    /// ranking.
+    ///
+    /// `promote` is called once on every item as it comes in and gives the
+    /// score the item should be promoted with, if any. How promoted items
+    /// are moved around depends on `mode`.
+    pub fn set_promotion(
+        &mut self,
+        promotion: Option<(PromotionFn<I>, PromotionMode)>,
+    ) {
+        self.promotion = promotion;
+        self.promoted.clear();
+        self.promotions_scanned = 0;
+        self.ranking_outdated = true;
+    }
+
+    /// Find out which of the items that came in since the last scan should
+    /// be promoted, returning whether any should.
+    fn scan_promotions(&mut self) -> bool {
+        let Some((promote, _)) = &self.promotion else {
+            return false;
+        };
+        let snapshot = self.inner.snapshot();
+        let end = snapshot
+            .item_count()
+            .min(self.promotions_scanned + promotion::SCAN_BATCH_SIZE);
+        let found = self.promoted.len();
+        while self.promotions_scanned < end {
+            // items may not be initialized in the order they were pushed
+            let Some(item) = snapshot.get_item(self.promotions_scanned) else {
+                break;
+            };
+            if let Some(score) = promote(&item.data.item) {
+                self.promoted
+                    .insert(item.data.index, (self.promotions_scanned, score));
+            }
+            self.promotions_scanned += 1;
+        }
+        self.promoted.len() > found
+    }
+
+    fn update_promotions(&mut self) {
+        let Some((_, mode)) = &self.promotion else {
+            self.promotions = Promotions::default();
+            return;
+        };
+        let mode = *mode;
+        if self.promoted.is_empty() {
+            self.promotions = Promotions::default();
+            return;
+        }
+        let promoted = self.promoted_positions();
+        let moves = match mode {
+            PromotionMode::TieBreaker if self.regex.is_none() && self.sort => {
+                let snapshot = self.inner.snapshot();
+                let mut scorer = self.scorer.lock();
+                let pattern = snapshot.pattern();
+                let mut score_at = |position: u32| {
+                    snapshot
+                        .get_matched_item(self.ranked_position(position)?)
+                        .and_then(|item| {
+                            pattern.score(item.matcher_columns, &mut scorer)
+                        })
+                };
+                promoted
+                    .into_iter()
+                    .map(|(position, score)| {
+                        // matches are sorted by decreasing score so the
+                        // first one with the same score can be looked up
+                        let item_score = score_at(position);
+                        let (mut low, mut high) = (0, position);
+                        while low < high {
+                            let mid = low + (high - low) / 2;
+                            if score_at(mid) > item_score {
+                                low = mid + 1;
+                            } else {
+                                high = mid;
+                            }
+                        }
+                        (low, position, score)
+                    })
+                    .collect()
+            }
+            // matches that aren't ranked by score are all tied
+            PromotionMode::Boost | PromotionMode::TieBreaker => promoted
+                .into_iter()
+                .map(|(position, score)| (0, position, score))
+                .collect(),
+        };
+        self.promotions = Promotions::new(moves);
+    }
+
+    /// The positions in the results, before promotions, of the promoted
+    /// items that match along with their score.
+    fn promoted_positions(&self) -> Vec<(u32, f64)> {
+        let snapshot = self.inner.snapshot();
+        if self.regex.is_none() && self.order.is_none() {
+            // promoted items can be looked up in nucleo's ranking directly
+            let mut scorer = self.scorer.lock();
+            let found = self
+                .promoted
+                .values()
+                .map(|&(index, score)| {
+                    order::nucleo_position(snapshot, &mut scorer, index)
+                        .map(|position| position.map(|p| (p, score)))
+                })
+                .collect::<Result<Vec<_>, ()>>();
+            if let Ok(found) = found {
+                return found.into_iter().flatten().collect();
+            }
+        }
+        // otherwise, go through the results until they were all found
+        let mut positions = Vec::new();
+        let mut position = 0;
+        while positions.len() < self.promoted.len()
+            && position < self.ranked_count()
+        {
+            if let Some(&(_, score)) = self
+                .ranked_position(position)
+                .and_then(|p| snapshot.get_matched_item(p))
+                .and_then(|item| self.promoted.get(&item.data.index))
+            {
+                positions.push((position, score));
+            }
+            position += 1;
+        }
+        positions
+    }
+
+    /// Remove all items from the fuzzy matcher while keeping the current
+    /// pattern.
+    ///
+    /// Previously created injectors are disconnected from the matcher, which
+    /// means a new injector should be created to push items afterwards.
+    pub fn restart(&mut self) {
+        self.inner.restart(false);
+        // previous injectors keep counting on their own
+        self.pushed_item_count = Arc::new(AtomicU32::new(0));
+        self.processed_item_count = Arc::new(AtomicU32::new(0));
+        if let Some(regex) = &mut self.regex {
+            regex.reset();
+        }
+        self.promoted.clear();
+        self.promotions_scanned = 0;
+        self.ranking_outdated = true;
     }
 
     /// Get an injector that can be used to push items into the fuzzy matcher.
@@ -98,7 +371,11 @@ where
     /// );
     /// ```
     pub fn injector(&self) -> Injector<I> {
-        Injector::new(self.inner.injector())
+        Injector::new(
+            self.inner.injector(),
+            self.pushed_item_count.clone(),
+            self.processed_item_count.clone(),
+        )
     }
 
     /// Find items that match the given pattern.
@@ -107,17 +384,117 @@ where
     /// The `Matcher` will keep track of the last pattern and only reparse the
     /// pattern if it has changed, allowing for more efficient matching when
     /// `self.last_pattern` is a prefix of the new `pattern`.
+    ///
+    /// This stops filtering items with a regex if `find_regex` was used
+    /// before.
     pub fn find(&mut self, pattern: &str) {
+        if self.regex.take().is_some() {
+            self.ranking_outdated = true;
+        }
         if pattern != self.last_pattern {
+            self.reparse(pattern, false);
+        }
+    }
+
+    /// Filter items with the given regular expression instead of fuzzy
+    /// matching them.
+    ///
+    /// Matching items are kept in the order they were pushed in, and the
+    /// parts of their text covered by the regex's capture groups (or by the
+    /// whole match if there are none) are highlighted.
+    /// If `pattern` isn't a valid regex, the current filter is left as is.
+    pub fn find_regex(&mut self, pattern: &str) -> Result<(), regex::Error> {
+        if self.regex.as_ref().is_some_and(|r| r.as_str() == pattern) {
+            return Ok(());
+        }
+        let regex = Regex::new(pattern)?;
+        if !self.last_pattern.is_empty() {
+            self.reparse("", true);
+        }
+        self.regex = Some(RegexFilter::new(regex));
+        self.ranking_outdated = true;
+        Ok(())
+    }
+
+    /// Match patterns case-sensitively and without normalizing unicode
+    /// characters, instead of only doing so when the pattern contains
+    /// uppercase (resp. non-ascii) characters.
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool) {
+        if case_sensitive != self.case_sensitive {
+            self.case_sensitive = case_sensitive;
+            let pattern = self.last_pattern.clone();
+            self.reparse(&pattern, true);
+        }
+    }
+
+    /// Parse the given pattern for each column.
+    ///
+    /// Columns whose pattern didn't change are left alone unless `force` is
+    /// set, in which case every column is parsed from scratch.
+    fn reparse(&mut self, pattern: &str, force: bool) {
+        let (case_matching, normalization) = if self.case_sensitive {
+            (CaseMatching::Respect, Normalization::Never)
+        } else if self.ignore_case {
+            (CaseMatching::Smart, Normalization::Smart)
+        } else {
+            (CaseMatching::Respect, Normalization::Smart)
+        };
+        if force {
+            // the same pattern may now match different items
+            self.ranking_outdated = true;
+        }
+        // the results are usually looked at from the top again
+        self.order_window = order::MIN_WINDOW;
+        let patterns = self.column_patterns(pattern);
+        for (column, (pattern, last_pattern)) in patterns
+            .iter()
+            .zip(&mut self.last_column_patterns)
+            .enumerate()
+        {
+            if !force && pattern == last_pattern {
+                continue;
+            }
             self.inner.pattern.reparse(
-                0,
+                column,
                 pattern,
-                nucleo::pattern::CaseMatching::Smart,
-                nucleo::pattern::Normalization::Smart,
-                pattern.starts_with(&self.last_pattern),
+                case_matching,
+                normalization,
+                !force && pattern.starts_with(last_pattern.as_str()),
             );
-            self.last_pattern = pattern.to_string();
+            last_pattern.clone_from(pattern);
+        }
+        self.last_pattern = pattern.to_string();
+    }
+
+    /// Split the given pattern into the patterns to match each column with.
+    ///
+    /// Words prefixed with a column's name followed by a colon go to that
+    /// column, and the others go to the main dimension. The name may be
+    /// preceded by nucleo's special characters which then apply to the
+    /// rest of the word (e.g. `!path:test` excludes paths containing
+    /// "test").
+    fn column_patterns(&self, pattern: &str) -> Vec<String> {
+        if self.columns.is_empty() {
+            return vec![pattern.to_string()];
+        }
+        let mut words = vec![Vec::new(); self.columns.len() + 1];
+        for word in pattern.split_whitespace() {
+            let term = word.trim_start_matches(['!', '^', '\'']);
+            let modifiers = &word[..word.len() - term.len()];
+            let column = term.split_once(':').and_then(|(name, term)| {
+                let column = self.columns.iter().position(|c| c == name)?;
+                Some((column + 1, term))
+            });
+            match column {
+                // the column's term hasn't been typed in yet
+                Some((_, "")) => {}
+                Some((column, term)) => {
+                    words[column].push(format!("{modifiers}{term}"));
+                }
+                None => words[0].push(word.to_string()),
+            }
         }
+        words.into_iter().map(|w| w.join(" ")).collect()
     }
 
     /// Get the matched items.
@@ -155,33 +532,66 @@ where
         num_entries: u32,
         offset: u32,
     ) -> Vec<matched_item::MatchedItem<I>> {
+        let end = (num_entries + offset).min(self.ranked_count());
+        if self.order.as_ref().is_some_and(|o| o.len() < end as usize) {
+            // leave some room to scroll before doing this again
+            self.order_window = end.saturating_mul(2).max(order::MIN_WINDOW);
+            self.order = self.order(self.order_window);
+        }
         let snapshot = self.inner.snapshot();
         self.total_item_count = snapshot.item_count();
-        self.matched_item_count = snapshot.matched_item_count();
+        self.matched_item_count = self.ranked_count();
 
         let mut col_indices = Vec::new();
         let mut matcher = lazy::MATCHER.lock();
+        let columns = self.columns.len();
 
-        snapshot
-            .matched_items(
-                offset..(num_entries + offset).min(self.matched_item_count),
-            )
+        let regex = self.regex.as_ref();
+        let positions =
+            offset..(num_entries + offset).min(self.matched_item_count);
+        positions
+            .filter_map(|position| {
+                snapshot.get_matched_item(self.nucleo_position(position)?)
+            })
             .map(move |item| {
-                snapshot.pattern().column_pattern(0).indices(
-                    item.matcher_columns[0].slice(..),
-                    &mut matcher,
-                    &mut col_indices,
-                );
-                col_indices.sort_unstable();
-                col_indices.dedup();
+                let matched_string = item.matcher_columns[0].to_string();
+                if let Some(regex) = regex {
+                    col_indices = regex.indices(&matched_string);
+                } else {
+                    snapshot.pattern().column_pattern(0).indices(
+                        item.matcher_columns[0].slice(..),
+                        &mut matcher,
+                        &mut col_indices,
+                    );
+                    col_indices.sort_unstable();
+                    col_indices.dedup();
+                }
 
                 let indices = col_indices.drain(..);
 
-                let matched_string = item.matcher_columns[0].to_string();
+                let column_indices = (1..=columns)
+                    .map(|column| {
+                        let pattern =
+                            snapshot.pattern().column_pattern(column);
+                        let mut indices = Vec::new();
+                        if !pattern.atoms.is_empty() {
+                            pattern.indices(
+                                item.matcher_columns[column].slice(..),
+                                &mut matcher,
+                                &mut indices,
+                            );
+                            indices.sort_unstable();
+                            indices.dedup();
+                        }
+                        indices
+                    })
+                    .collect();
+
                 matched_item::MatchedItem {
-                    inner: item.data.clone(),
+                    inner: item.data.item.clone(),
                     matched_string,
                     match_indices: indices.collect(),
+                    column_indices,
                 }
             })
             .collect()
@@ -207,13 +617,62 @@ where
         index: u32,
     ) -> Option<matched_item::MatchedItem<I>> {
         let snapshot = self.inner.snapshot();
+        let index = self.nucleo_position(index)?;
         snapshot.get_matched_item(index).map(|item| {
             let matched_string = item.matcher_columns[0].to_string();
             matched_item::MatchedItem {
-                inner: item.data.clone(),
+                inner: item.data.item.clone(),
                 matched_string,
                 match_indices: Vec::new(),
+                column_indices: Vec::new(),
             }
         })
     }
+
+    /// The position in nucleo's matches of the item at the given position in
+    /// the results.
+    fn nucleo_position(&self, position: u32) -> Option<u32> {
+        self.ranked_position(self.promotions.resolve(position))
+    }
+
+    /// The position in nucleo's matches of the item at the given position in
+    /// the results, before promotions.
+    fn ranked_position(&self, position: u32) -> Option<u32> {
+        match (&self.regex, &self.order) {
+            (Some(regex), _) => regex.get(position),
+            (None, Some(order)) => match order.get(position as usize) {
+                Some(position) => Some(*position),
+                // past the window the order was worked out for, which
+                // `results` usually extends beforehand
+                None if order.len() < self.ranked_count() as usize => self
+                    .order(position + 1)
+                    .and_then(|order| order.get(position as usize).copied()),
+                None => None,
+            },
+            (None, None) => Some(position),
+        }
+    }
+
+    /// Work out the order of the first `window` matches (see
+    /// `order::order`).
+    fn order(&self, window: u32) -> Option<Vec<u32>> {
+        if self.regex.is_some() {
+            return None;
+        }
+        order::order(
+            self.inner.snapshot(),
+            &mut self.scorer.lock(),
+            self.sort,
+            &self.tiebreak,
+            window,
+        )
+    }
+
+    /// The number of matches, before promotions.
+    fn ranked_count(&self) -> u32 {
+        match &self.regex {
+            Some(regex) => regex.len(),
+            None => self.inner.snapshot().matched_item_count(),
+        }
+    }
 }
@@ -0,0 +1,199 @@
+use std::cmp::{Ordering, Reverse};
+
+use nucleo::{Snapshot, Utf32Str, Utf32String};
+
+use super::{config::TieBreak, Indexed};
+
+/// The minimum number of matches the order is worked out for (see
+/// `order`), so that scrolling a little doesn't require working it out
+/// again.
+pub(crate) const MIN_WINDOW: u32 = 256;
+
+/// What the order of the matches depends on, used to tell whether it needs
+/// to be worked out again.
+///
+/// Two snapshots with the same key have the same matches as long as the
+/// items and the way the pattern is parsed didn't change in between (which
+/// the matcher keeps track of with `ranking_outdated`).
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Key {
+    item_count: u32,
+    matched_item_count: u32,
+    pattern: String,
+}
+
+impl Key {
+    pub(crate) fn new<I>(
+        snapshot: &Snapshot<Indexed<I>>,
+        pattern: &str,
+    ) -> Self
+    where
+        I: Sync + Send + Clone + 'static,
+    {
+        Self {
+            item_count: snapshot.item_count(),
+            matched_item_count: snapshot.matched_item_count(),
+            pattern: pattern.to_string(),
+        }
+    }
+}
+
+/// Work out the order in which the first `window` matches of the snapshot
+/// should be shown, as positions in nucleo's matches, if it differs from
+/// nucleo's ranking.
+///
+/// Without `sort`, matches are kept in the order they were pushed in.
+/// Otherwise, matches with the same score are ordered by the given
+/// criteria, remaining ties being left in nucleo's order.
+///
+/// Only the matches that may end up in the window are looked at closely,
+/// the others are left out with a partial sort.
+pub(crate) fn order<I>(
+    snapshot: &Snapshot<Indexed<I>>,
+    matcher: &mut nucleo::Matcher,
+    sort: bool,
+    tiebreak: &[TieBreak],
+    window: u32,
+) -> Option<Vec<u32>>
+where
+    I: Sync + Send + Clone + 'static,
+{
+    if snapshot.pattern().is_empty() {
+        // nucleo already keeps the matches in the order they were pushed in
+        return None;
+    }
+    let count = snapshot.matched_item_count();
+    let window = window.min(count) as usize;
+    if !sort {
+        let mut positions = (0..count)
+            .filter_map(|position| {
+                let item = snapshot.get_matched_item(position)?;
+                Some((item.data.index, position))
+            })
+            .collect::<Vec<_>>();
+        keep_first(&mut positions, window, Ord::cmp);
+        return Some(positions.into_iter().map(|(_, p)| p).collect());
+    }
+    if tiebreak.is_empty() || tiebreak == super::config::DEFAULT_TIEBREAK {
+        return None;
+    }
+
+    let pattern = snapshot.pattern();
+    let mut ranked: Vec<(u32, u32, u32, Utf32Str)> = Vec::new();
+    for position in 0..count {
+        let Some(item) = snapshot.get_matched_item(position) else {
+            continue;
+        };
+        let Some(score) = pattern.score(item.matcher_columns, matcher) else {
+            continue;
+        };
+        // matches are already sorted by decreasing score, so the ones past
+        // the window that don't share the last one's score stay out of it
+        if ranked.len() >= window
+            && ranked.last().is_some_and(|last| last.0 != score)
+        {
+            break;
+        }
+        ranked.push((
+            score,
+            position,
+            item.data.index,
+            item.matcher_columns[0].slice(..),
+        ));
+    }
+    keep_first(&mut ranked, window, |a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| {
+                let keys = |index, haystack| {
+                    tiebreak
+                        .iter()
+                        .map(move |criterion| key(*criterion, index, haystack))
+                };
+                keys(a.2, a.3).cmp(keys(b.2, b.3))
+            })
+            .then(a.1.cmp(&b.1))
+    });
+    Some(ranked.into_iter().map(|(_, p, _, _)| p).collect())
+}
+
+/// Only keep the first `n` elements of `items` in the given order, sorted.
+fn keep_first<T, F>(items: &mut Vec<T>, n: usize, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if n == 0 {
+        items.clear();
+        return;
+    }
+    if n < items.len() {
+        items.select_nth_unstable_by(n - 1, &mut compare);
+        items.truncate(n);
+    }
+    items.sort_unstable_by(compare);
+}
+
+/// Look up the position in nucleo's matches of the item with the given
+/// index (in nucleo's items) without going through all the matches.
+///
+/// This relies on nucleo ranking matches by decreasing score, then by
+/// length and then in the order they were pushed in (or only in that order
+/// when the pattern is empty). Returns `Ok(None)` if the item doesn't match
+/// and `Err(())` if it wasn't found where expected.
+pub(crate) fn nucleo_position<I>(
+    snapshot: &Snapshot<Indexed<I>>,
+    matcher: &mut nucleo::Matcher,
+    index: u32,
+) -> Result<Option<u32>, ()>
+where
+    I: Sync + Send + Clone + 'static,
+{
+    let item = snapshot.get_item(index).ok_or(())?;
+    let pattern = snapshot.pattern();
+    let position = if pattern.is_empty() {
+        index
+    } else {
+        let Some(score) = pattern.score(item.matcher_columns, matcher) else {
+            return Ok(None);
+        };
+        let target =
+            (Reverse(score), len(item.matcher_columns), item.data.index);
+        let (mut low, mut high) = (0, snapshot.matched_item_count());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let other = snapshot.get_matched_item(mid).ok_or(())?;
+            let score = pattern
+                .score(other.matcher_columns, matcher)
+                .unwrap_or_default();
+            if (Reverse(score), len(other.matcher_columns), other.data.index)
+                < target
+            {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    };
+    match snapshot.get_matched_item(position) {
+        Some(found) if found.data.index == item.data.index => {
+            Ok(Some(position))
+        }
+        _ => Err(()),
+    }
+}
+
+/// The length nucleo ranks matches with the same score by.
+fn len(columns: &[Utf32String]) -> usize {
+    columns.iter().map(Utf32String::len).sum()
+}
+
+/// The key used to order matches by the given criterion (lowest first).
+fn key(criterion: TieBreak, index: u32, haystack: Utf32Str) -> usize {
+    match criterion {
+        TieBreak::Index => index as usize,
+        TieBreak::Length => haystack.len(),
+        TieBreak::PathDepth => {
+            haystack.chars().filter(|c| *c == '/' || *c == '\\').count()
+        }
+    }
+}
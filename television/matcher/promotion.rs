@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+/// Gives the score some items should be promoted with in the matcher's
+/// results (the higher, the better), or `None` to leave them where they are.
+pub type PromotionFn<I> = Arc<dyn Fn(&I) -> Option<f64> + Send + Sync>;
+
+/// The maximum number of items checked for promotion each time the matcher
+/// ticks, so that large sources don't block the UI.
+pub(crate) const SCAN_BATCH_SIZE: u32 = 100_000;
+
+/// How promoted items are moved around in the matcher's results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PromotionMode {
+    /// Move promoted items above all other matches.
+    #[default]
+    Boost,
+    /// Only move promoted items above the other matches with the same score.
+    TieBreaker,
+}
+
+/// The matched items that were moved from their position in nucleo's
+/// ranking.
+///
+/// Only a handful of items are expected to be promoted, which means the
+/// final position of every other item can be worked out on the fly instead
+/// of re-sorting all matches.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Promotions {
+    /// `(target, source)` pairs where `source` is the item's position in
+    /// nucleo's ranking and `target` the position (in nucleo's ranking too)
+    /// the item is moved in front of, in the order they should appear.
+    moves: Vec<(u32, u32)>,
+    /// The source positions of the moved items, in ascending order.
+    sources: Vec<u32>,
+}
+
+impl Promotions {
+    /// Build the promotions from `(target, source, score)` triples.
+    ///
+    /// Items moved in front of the same target are ordered by decreasing
+    /// score and then by their original position. Targets should never be
+    /// greater than their source.
+    pub fn new(mut moves: Vec<(u32, u32, f64)>) -> Self {
+        moves.sort_by(|a, b| {
+            a.0.cmp(&b.0)
+                .then_with(|| b.2.total_cmp(&a.2))
+                .then_with(|| a.1.cmp(&b.1))
+        });
+        let mut sources = moves.iter().map(|m| m.1).collect::<Vec<_>>();
+        sources.sort_unstable();
+        Self {
+            moves: moves.into_iter().map(|m| (m.0, m.1)).collect(),
+            sources,
+        }
+    }
+
+    /// The position in nucleo's ranking of the item at the given position in
+    /// the final results.
+    pub fn resolve(&self, position: u32) -> u32 {
+        let mut remaining = position;
+        let mut start = 0;
+        for &(target, source) in &self.moves {
+            let untouched =
+                target - start - self.sources_between(start, target);
+            if remaining < untouched {
+                return self.nth_untouched(start, remaining);
+            }
+            remaining -= untouched;
+            start = target;
+            if remaining == 0 {
+                return source;
+            }
+            remaining -= 1;
+        }
+        self.nth_untouched(start, remaining)
+    }
+
+    /// The number of moved items in `start..end`.
+    fn sources_between(&self, start: u32, end: u32) -> u32 {
+        let count = self.sources.partition_point(|s| *s < end)
+            - self.sources.partition_point(|s| *s < start);
+        u32::try_from(count).unwrap()
+    }
+
+    /// The position of the `n`-th item that wasn't moved, starting from
+    /// `start`.
+    fn nth_untouched(&self, start: u32, n: u32) -> u32 {
+        let mut position = start + n;
+        let first = self.sources.partition_point(|s| *s < start);
+        for source in &self.sources[first..] {
+            if *source <= position {
+                position += 1;
+            } else {
+                break;
+            }
+        }
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve_all(promotions: &Promotions, count: u32) -> Vec<u32> {
+        (0..count).map(|p| promotions.resolve(p)).collect()
+    }
+
+    #[test]
+    fn test_no_promotions() {
+        let promotions = Promotions::default();
+        assert_eq!(resolve_all(&promotions, 4), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_boost() {
+        let promotions =
+            Promotions::new(vec![(0, 3, 1.0), (0, 5, 2.0), (0, 0, 1.0)]);
+        assert_eq!(resolve_all(&promotions, 7), vec![5, 0, 3, 1, 2, 4, 6]);
+    }
+
+    #[test]
+    fn test_tie_breaker() {
+        // e.g. scores [9, 9, 5, 5, 5, 1] with positions 1 and 4 promoted
+        let promotions = Promotions::new(vec![(0, 1, 1.0), (2, 4, 1.0)]);
+        assert_eq!(resolve_all(&promotions, 6), vec![1, 0, 4, 2, 3, 5]);
+    }
+}
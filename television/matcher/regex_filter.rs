@@ -0,0 +1,146 @@
+use nucleo::Snapshot;
+use regex::Regex;
+
+/// The maximum number of items checked against the regex each time the
+/// matcher ticks, so that large sources don't block the UI.
+const SCAN_BATCH_SIZE: u32 = 100_000;
+
+/// Filters the matcher's items with a regular expression instead of fuzzy
+/// matching them.
+///
+/// The fuzzy pattern is left empty while the filter is active, which means
+/// nucleo matches every item in the order they were pushed. Items are then
+/// checked against the regex incrementally as they come in, and the
+/// positions of the ones that match are kept in order.
+#[derive(Debug, Clone)]
+pub(crate) struct RegexFilter {
+    regex: Regex,
+    /// The positions (in nucleo's matches) of the items matching the regex,
+    /// in ascending order.
+    matches: Vec<u32>,
+    /// The number of nucleo's matches checked against the regex so far.
+    scanned: u32,
+}
+
+impl RegexFilter {
+    pub fn new(regex: Regex) -> Self {
+        Self {
+            regex,
+            matches: Vec::new(),
+            scanned: 0,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// Forget about the items checked so far.
+    pub fn reset(&mut self) {
+        self.matches.clear();
+        self.scanned = 0;
+    }
+
+    /// The number of items matching the regex so far.
+    pub fn len(&self) -> u32 {
+        u32::try_from(self.matches.len()).unwrap()
+    }
+
+    /// The position in nucleo's matches of the `n`-th item matching the
+    /// regex.
+    pub fn get(&self, n: u32) -> Option<u32> {
+        self.matches.get(n as usize).copied()
+    }
+
+    /// Check the items that came in since the last scan against the regex.
+    ///
+    /// Returns whether new matches were found.
+    pub fn scan<I>(&mut self, snapshot: &Snapshot<I>) -> bool
+    where
+        I: Sync + Send + 'static,
+    {
+        if !snapshot.pattern().is_empty() {
+            // nucleo hasn't caught up with the empty pattern yet
+            return false;
+        }
+        let count = snapshot.matched_item_count();
+        if count < self.scanned {
+            // the items were cleared in the meantime
+            self.reset();
+        }
+        let end = count.min(self.scanned + SCAN_BATCH_SIZE);
+        let found = self.matches.len();
+        for position in self.scanned..end {
+            if let Some(item) = snapshot.get_matched_item(position) {
+                let haystack = item.matcher_columns[0].to_string();
+                if self.regex.is_match(&haystack) {
+                    self.matches.push(position);
+                }
+            }
+        }
+        self.scanned = end;
+        self.matches.len() > found
+    }
+
+    /// Whether every item in the snapshot was checked against the regex.
+    pub fn is_done<I>(&self, snapshot: &Snapshot<I>) -> bool
+    where
+        I: Sync + Send + 'static,
+    {
+        snapshot.pattern().is_empty()
+            && self.scanned >= snapshot.matched_item_count()
+    }
+
+    /// The indices of the characters of `haystack` to highlight.
+    ///
+    /// These are the characters covered by the regex's capture groups, or by
+    /// the whole match if the regex doesn't have any, for every match in
+    /// `haystack`.
+    pub fn indices(&self, haystack: &str) -> Vec<u32> {
+        let groups = if self.regex.captures_len() > 1 {
+            1..self.regex.captures_len()
+        } else {
+            0..1
+        };
+        let spans = self
+            .regex
+            .captures_iter(haystack)
+            .flat_map(|captures| {
+                groups
+                    .clone()
+                    .filter_map(|i| captures.get(i))
+                    .map(|m| m.range())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut indices = Vec::new();
+        for (index, (offset, _)) in (0..).zip(haystack.char_indices()) {
+            if spans.iter().any(|span| span.contains(&offset)) {
+                indices.push(index);
+            }
+        }
+        indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indices(regex: &str, haystack: &str) -> Vec<u32> {
+        RegexFilter::new(Regex::new(regex).unwrap()).indices(haystack)
+    }
+
+    #[test]
+    fn test_indices_of_whole_matches() {
+        assert_eq!(indices("b+", "abba ab"), vec![1, 2, 6]);
+        assert_eq!(indices("x", "abc"), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_indices_of_capture_groups() {
+        assert_eq!(indices(r"(\w+)\.(rs)", "main.rs"), vec![0, 1, 2, 3, 5, 6]);
+        // character indices rather than byte offsets
+        assert_eq!(indices("é(t)é", "étés"), vec![1]);
+    }
+}
@@ -0,0 +1,101 @@
+use rustc_hash::FxHashMap;
+use tracing::debug;
+
+use crate::{previewer::Preview, utils::cache::RingSet};
+
+/// A cache of the previews produced by a preview command, keyed by the
+/// command they were produced with (i.e. formatted with their entry).
+///
+/// Once the cache is full, the least recently used preview is evicted.
+/// A cache with a capacity of 0 doesn't keep anything.
+#[derive(Debug)]
+pub struct PreviewCache {
+    previews: FxHashMap<String, Preview>,
+    ring_set: RingSet<String>,
+    capacity: usize,
+}
+
+impl PreviewCache {
+    pub fn new(capacity: usize) -> Self {
+        PreviewCache {
+            previews: FxHashMap::default(),
+            ring_set: RingSet::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// The preview produced by the given command, if it's in the cache.
+    pub fn get(&mut self, command: &str) -> Option<Preview> {
+        let preview = self.previews.get(command)?.clone();
+        self.ring_set.refresh(&command.to_string());
+        Some(preview)
+    }
+
+    /// Whether the preview produced by the given command is in the cache,
+    /// without counting as a use of it.
+    pub fn contains(&self, command: &str) -> bool {
+        self.previews.contains_key(command)
+    }
+
+    pub fn insert(&mut self, command: String, preview: Preview) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.previews.contains_key(&command) {
+            self.ring_set.refresh(&command);
+        } else if let Some(oldest) = self.ring_set.push(command.clone()) {
+            debug!("Evicting preview for {:?} from the cache", oldest);
+            self.previews.remove(&oldest);
+        }
+        self.previews.insert(command, preview);
+    }
+
+    /// Forget all cached previews, e.g. when they might be outdated.
+    pub fn clear(&mut self) {
+        self.previews.clear();
+        self.ring_set.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.previews.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.previews.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(content: &str) -> Preview {
+        Preview::new("title", content.to_string(), None, 1)
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = PreviewCache::new(2);
+        cache.insert("cat a".into(), preview("a"));
+        cache.insert("cat b".into(), preview("b"));
+        // `cat a` is now more recent than `cat b`
+        assert_eq!(cache.get("cat a").unwrap().content, "a");
+        cache.insert("cat c".into(), preview("c"));
+
+        assert!(cache.get("cat b").is_none());
+        assert_eq!(cache.get("cat a").unwrap().content, "a");
+        assert_eq!(cache.get("cat c").unwrap().content, "c");
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert!(cache.get("cat a").is_none());
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut cache = PreviewCache::new(0);
+        cache.insert("cat a".into(), preview("a"));
+        assert!(cache.get("cat a").is_none());
+    }
+}
@@ -1,45 +1,217 @@
 use std::{
     cmp::Ordering,
+    fs::File,
+    io,
+    pin::Pin,
+    process::Stdio,
+    sync::{
+        atomic::{self, AtomicU64},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
 use devicons::FileIcon;
+use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
 use tokio::{
-    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader},
+    process::Child,
+    sync::{
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+        Semaphore,
+    },
+    task::JoinHandle,
     time::timeout,
 };
 use tracing::debug;
 
 use crate::{
     channels::{entry::Entry, preview::PreviewCommand},
+    previewer::cache::PreviewCache,
     utils::{
         command::shell_command,
+        process::{kill_process_group, ProcessTracker},
         strings::{replace_non_printable, ReplaceNonPrintableConfig},
     },
 };
 
+pub mod cache;
+pub mod pty;
 pub mod state;
 
+/// The configuration of the previewer.
+///
+/// This is the `[previewer]` section of the configuration file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
-    request_max_age: Duration,
-    job_timeout: Duration,
+    /// How long a preview request can wait to be handled before it's
+    /// dropped (in milliseconds).
+    #[serde(with = "millis")]
+    pub request_max_age: Duration,
+    /// How long a preview command can go without outputting anything
+    /// before it's stopped and the preview marked as timed out (in
+    /// milliseconds).
+    #[serde(with = "millis")]
+    pub job_timeout: Duration,
+    /// How long the selection has to stay on an entry before its preview
+    /// command is run (in milliseconds).
+    #[serde(with = "millis")]
+    pub debounce: Duration,
+    /// The number of previews to keep around so that entries that were
+    /// recently previewed don't need to be previewed again (`0` disables
+    /// the cache).
+    pub cache_size: usize,
+    /// The number of entries on each side of the selected one whose
+    /// previews are computed ahead of time so that they're ready when the
+    /// selection moves to them (`0` disables prefetching).
+    pub prefetch: usize,
+    /// The maximum number of prefetched previews computed at once.
+    pub prefetch_workers: usize,
+    /// The maximum number of lines read from a preview command, after
+    /// which it's stopped.
+    pub max_lines: usize,
+    /// Whether preview commands run inside a pseudo-terminal of the size of
+    /// the preview rather than with their output piped, so that they output
+    /// colors and size their output as they would in a terminal (only
+    /// supported on unix).
+    pub pty: bool,
 }
 
 pub const DEFAULT_REQUEST_MAX_AGE: Duration = Duration::from_millis(1000);
 pub const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_millis(500);
+pub const DEFAULT_DEBOUNCE: Duration = Duration::ZERO;
+pub const DEFAULT_CACHE_SIZE: usize = 50;
+pub const DEFAULT_PREFETCH: usize = 2;
+pub const DEFAULT_PREFETCH_WORKERS: usize = 2;
+pub const DEFAULT_MAX_LINES: usize = 10_000;
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             request_max_age: DEFAULT_REQUEST_MAX_AGE,
             job_timeout: DEFAULT_JOB_TIMEOUT,
+            debounce: DEFAULT_DEBOUNCE,
+            cache_size: DEFAULT_CACHE_SIZE,
+            prefetch: DEFAULT_PREFETCH,
+            prefetch_workers: DEFAULT_PREFETCH_WORKERS,
+            max_lines: DEFAULT_MAX_LINES,
+            pty: false,
+        }
+    }
+}
+
+impl Config {
+    /// Set how long a preview request can wait to be handled.
+    pub fn request_max_age(mut self, request_max_age: Duration) -> Self {
+        self.request_max_age = request_max_age;
+        self
+    }
+
+    /// Set how long a preview command can go without outputting anything.
+    pub fn job_timeout(mut self, job_timeout: Duration) -> Self {
+        self.job_timeout = job_timeout;
+        self
+    }
+
+    /// Set how long the selection has to stay on an entry before it's
+    /// previewed.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Set the number of previews to keep around.
+    pub fn cache_size(mut self, cache_size: usize) -> Self {
+        self.cache_size = cache_size;
+        self
+    }
+
+    /// Set the number of entries on each side of the selected one to
+    /// prefetch the previews of.
+    pub fn prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Set the maximum number of prefetched previews computed at once.
+    pub fn prefetch_workers(mut self, prefetch_workers: usize) -> Self {
+        self.prefetch_workers = prefetch_workers;
+        self
+    }
+
+    /// Set the maximum number of lines read from a preview command.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    /// Set whether preview commands run inside a pseudo-terminal.
+    pub fn pty(mut self, pty: bool) -> Self {
+        self.pty = pty;
+        self
+    }
+}
+
+/// (De)serialize durations as a number of milliseconds.
+mod millis {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        duration: &Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(
+            u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+/// The environment variables preview commands get the size of the preview
+/// through.
+pub const PREVIEW_COLUMNS_ENV: &str = "TV_PREVIEW_COLUMNS";
+pub const PREVIEW_LINES_ENV: &str = "TV_PREVIEW_LINES";
+
+/// The number of columns and lines available to display a preview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PreviewSize {
+    pub columns: u16,
+    pub lines: u16,
+}
+
+impl Default for PreviewSize {
+    /// The size of a typical terminal, until the preview is displayed.
+    fn default() -> Self {
+        Self {
+            columns: 80,
+            lines: 24,
         }
     }
 }
 
 #[derive(PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
 pub enum Request {
     Preview(Ticket),
+    /// Forget the cached previews, e.g. because they might be outdated.
+    InvalidateCache,
+    /// Compute the previews of the given entries ahead of time, nearest
+    /// first, for them to land in the cache.
+    ///
+    /// This replaces any prefetch that hasn't started yet.
+    Prefetch(Vec<Entry>),
+    /// The preview is now displayed with the given size.
+    Resize(PreviewSize),
     Shutdown,
 }
 
@@ -53,8 +225,18 @@ impl Ord for Request {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
             // Shutdown signals always have priority
-            (Self::Shutdown, _) => Ordering::Greater,
-            (_, Self::Shutdown) => Ordering::Less,
+            (Self::Shutdown, _)
+            | (
+                _,
+                Self::InvalidateCache | Self::Prefetch(_) | Self::Resize(_),
+            ) => Ordering::Greater,
+            // invalidations, prefetches and resizes are set aside before
+            // picking the request to handle (see `handle_requests`)
+            (_, Self::Shutdown)
+            | (
+                Self::InvalidateCache | Self::Prefetch(_) | Self::Resize(_),
+                _,
+            ) => Ordering::Less,
             // Otherwise fall back to ticket age comparison
             (Self::Preview(t1), Self::Preview(t2)) => t1.cmp(t2),
         }
@@ -98,6 +280,11 @@ pub struct Preview {
     pub content: String,
     pub icon: Option<FileIcon>,
     pub total_lines: u16,
+    /// Why the preview command couldn't be run, if it couldn't.
+    pub error: Option<String>,
+    /// Whether the preview command was stopped for taking too long to
+    /// output anything, in which case the preview might be incomplete.
+    pub timed_out: bool,
 }
 
 const DEFAULT_PREVIEW_TITLE: &str = "Select an entry to preview";
@@ -109,6 +296,8 @@ impl Default for Preview {
             content: String::new(),
             icon: None,
             total_lines: 1,
+            error: None,
+            timed_out: false,
         }
     }
 }
@@ -125,17 +314,88 @@ impl Preview {
             content,
             icon,
             total_lines,
+            error: None,
+            timed_out: false,
+        }
+    }
+
+    /// Add more lines to the preview.
+    ///
+    /// Chunks are made of whole lines, so that only the new ones need to be
+    /// counted.
+    pub fn append(&mut self, chunk: &str) {
+        self.content.push_str(chunk);
+        self.total_lines = self.total_lines.saturating_add(line_count(chunk));
+    }
+
+    /// A preview reporting that the preview command couldn't be run.
+    fn error(title: &str, error: String) -> Self {
+        Self {
+            error: Some(error.clone()),
+            ..Self::new(title, error, None, 1)
+        }
+    }
+
+    /// A preview reporting that the preview command timed out before it
+    /// output anything.
+    fn timed_out(title: &str) -> Self {
+        Self {
+            timed_out: true,
+            ..Self::new(title, String::new(), None, 1)
         }
     }
 }
 
+/// What the previewer sends over to the UI.
+#[derive(Debug, Clone)]
+pub enum Update {
+    /// A new preview, replacing whatever was previewed so far.
+    Preview(Preview),
+    /// More lines of the preview that was sent last, which is still being
+    /// streamed from its command.
+    Append(String),
+    /// The command of the preview that was sent last timed out.
+    TimedOut,
+}
+
 pub struct Previewer {
     config: Config,
     // FIXME: maybe use a bounded channel here with a single slot
     requests: UnboundedReceiver<Request>,
     last_job_entry: Option<Entry>,
     preview_command: PreviewCommand,
-    results: UnboundedSender<Preview>,
+    results: UnboundedSender<Update>,
+    processes: ProcessTracker,
+    /// The size previews are displayed with.
+    size: PreviewSize,
+    /// The job whose output is currently streamed to the UI.
+    ///
+    /// Jobs check it before sending anything over so that a job that was
+    /// superseded can't overwrite the preview of a more recent one.
+    current_job: Arc<Mutex<CurrentJob>>,
+    job_handle: Option<JoinHandle<()>>,
+    cache: PreviewCache,
+    /// Bumped every time the cache is invalidated so that previews that
+    /// were being computed at the time don't land in it.
+    cache_epoch: u64,
+    /// Bounds the number of prefetched previews computed at once.
+    prefetch_slots: Arc<Semaphore>,
+    /// Bumped every time new entries are to be prefetched so that outdated
+    /// prefetches that haven't started yet are dropped.
+    prefetch_generation: Arc<AtomicU64>,
+    /// The commands currently being prefetched.
+    prefetching: FxHashSet<String>,
+    finished_tx: UnboundedSender<Finished>,
+    finished_rx: UnboundedReceiver<Finished>,
+}
+
+/// A preview that was computed in the background, either for the selected
+/// entry or ahead of time.
+struct Finished {
+    command: String,
+    cache_epoch: u64,
+    /// `None` if the preview command didn't run to completion.
+    preview: Option<Preview>,
 }
 
 impl Previewer {
@@ -143,110 +403,808 @@ impl Previewer {
         preview_command: PreviewCommand,
         config: Config,
         receiver: UnboundedReceiver<Request>,
-        sender: UnboundedSender<Preview>,
+        sender: UnboundedSender<Update>,
+        processes: ProcessTracker,
     ) -> Self {
+        let (finished_tx, finished_rx) = unbounded_channel();
         Self {
+            size: PreviewSize::default(),
+            current_job: Arc::new(Mutex::new(CurrentJob::default())),
+            job_handle: None,
+            cache: PreviewCache::new(config.cache_size),
+            cache_epoch: 0,
+            prefetch_slots: Arc::new(Semaphore::new(config.prefetch_workers)),
+            prefetch_generation: Arc::new(AtomicU64::new(0)),
+            prefetching: FxHashSet::default(),
+            finished_tx,
+            finished_rx,
             config,
             requests: receiver,
             last_job_entry: None,
             preview_command,
             results: sender,
+            processes,
         }
     }
 
     pub async fn run(mut self) {
         let mut buffer = Vec::with_capacity(32);
         loop {
-            let num = self.requests.recv_many(&mut buffer, 32).await;
-            if num > 0 {
-                debug!("Previewer received {num} request(s)!");
-                // only keep the newest request
-                match buffer.drain(..).max().unwrap() {
-                    Request::Preview(ticket) => {
-                        if ticket.age() > self.config.request_max_age {
-                            debug!("Preview request is stale, skipping");
-                            continue;
-                        }
-                        let results_handle = self.results.clone();
-                        let command =
-                            self.preview_command.format_with(&ticket.entry);
-                        self.last_job_entry = Some(ticket.entry.clone());
-                        // try to execute the preview with a timeout
-                        match timeout(
-                            self.config.job_timeout,
-                            tokio::spawn(async move {
-                                try_preview(
-                                    &command,
-                                    &ticket.entry,
-                                    &results_handle,
-                                );
-                            }),
-                        )
-                        .await
-                        {
-                            Ok(_) => {
-                                debug!("Preview job completed successfully");
-                            }
-                            Err(e) => {
-                                debug!("Preview job timeout: {}", e);
-                            }
-                        }
+            tokio::select! {
+                // requests always come before finished previews
+                biased;
+                num = self.requests.recv_many(&mut buffer, 32) => {
+                    if num == 0 {
+                        debug!("Preview request channel closed and no messages left, breaking out of the previewer loop.");
+                        self.stop_job();
+                        self.processes.shutdown();
+                        break;
                     }
-                    Request::Shutdown => {
-                        debug!("Received shutdown signal, breaking out of the previewer loop.");
+                    debug!("Previewer received {num} request(s)!");
+                    if !self.handle_requests(&mut buffer) {
                         break;
                     }
                 }
-            } else {
-                debug!("Preview request channel closed and no messages left, breaking out of the previewer loop.");
-                break;
+                Some(finished) = self.finished_rx.recv() => {
+                    self.prefetching.remove(&finished.command);
+                    if finished.cache_epoch != self.cache_epoch {
+                        continue;
+                    }
+                    if let Some(preview) = finished
+                        .preview
+                        .filter(|preview| preview.error.is_none())
+                    {
+                        debug!("Caching the preview for {:?}", finished.command);
+                        self.cache.insert(finished.command, preview);
+                    }
+                }
             }
         }
     }
+
+    /// Handle a batch of requests, returning whether the previewer should
+    /// keep running.
+    fn handle_requests(&mut self, buffer: &mut Vec<Request>) -> bool {
+        // the cache is invalidated before handling any request that came
+        // along with the invalidation
+        // previews depend on the size they're displayed with
+        let size = buffer.iter().rev().find_map(|r| match r {
+            Request::Resize(size) => Some(*size),
+            _ => None,
+        });
+        buffer.retain(|r| !matches!(r, Request::Resize(_)));
+        if let Some(size) = size.filter(|size| *size != self.size) {
+            debug!("Preview resized to {:?}", size);
+            self.size = size;
+            self.invalidate_cache();
+        }
+        if buffer.contains(&Request::InvalidateCache) {
+            debug!("Invalidating the preview cache");
+            self.invalidate_cache();
+            buffer.retain(|r| *r != Request::InvalidateCache);
+        }
+        // only the most recent prefetch matters, and it comes after the
+        // actual request
+        let prefetch = buffer
+            .iter()
+            .rposition(|r| matches!(r, Request::Prefetch(_)))
+            .map(|i| buffer.swap_remove(i));
+        buffer.retain(|r| !matches!(r, Request::Prefetch(_)));
+
+        // only keep the newest request
+        match buffer.drain(..).max() {
+            Some(Request::Preview(ticket)) => self.preview(ticket),
+            Some(Request::Shutdown) => {
+                debug!("Received shutdown signal, breaking out of the previewer loop.");
+                self.stop_job();
+                self.processes.shutdown();
+                return false;
+            }
+            Some(
+                Request::InvalidateCache
+                | Request::Prefetch(_)
+                | Request::Resize(_),
+            )
+            | None => {}
+        }
+        if let Some(Request::Prefetch(entries)) = prefetch {
+            self.prefetch(entries);
+        }
+        true
+    }
+
+    fn preview(&mut self, ticket: Ticket) {
+        if ticket.age() > self.config.request_max_age {
+            debug!("Preview request is stale, skipping");
+            return;
+        }
+        let command = self
+            .preview_command
+            .format_with_size(&ticket.entry, self.size);
+        self.last_job_entry = Some(ticket.entry.clone());
+        let job_id = self.stop_job();
+        if let Some(preview) = self.cache.get(&command) {
+            debug!("Preview cache hit for {:?}", command);
+            self.results
+                .send(Update::Preview(preview))
+                .expect("Unable to send preview result to main thread.");
+            return;
+        }
+        let sink = StreamSink {
+            results: self.results.clone(),
+            job_id,
+            current_job: self.current_job.clone(),
+            started: false,
+        };
+        let job = PreviewJob {
+            delay: self.config.debounce.saturating_sub(ticket.age()),
+            command,
+            entry: ticket.entry,
+            processes: self.processes.clone(),
+            job_timeout: self.config.job_timeout,
+            max_lines: self.config.max_lines,
+            size: self.size,
+            pty: self.config.pty,
+        };
+        let cache_epoch = self.cache_epoch;
+        let finished = self.finished_tx.clone();
+        self.job_handle = Some(tokio::spawn(async move {
+            let preview = job.run(Some(sink)).await;
+            let _ = finished.send(Finished {
+                command: job.command,
+                cache_epoch,
+                preview,
+            });
+        }));
+    }
+
+    /// Stop the job currently streaming its preview, if any, returning the
+    /// id of the next job.
+    ///
+    /// Only the job's own process is killed, prefetches keep running.
+    fn stop_job(&mut self) -> u64 {
+        let (job_id, pid) = {
+            let mut current_job = self.current_job.lock();
+            current_job.id += 1;
+            (current_job.id, current_job.pid.take())
+        };
+        if let Some(handle) = self.job_handle.take() {
+            handle.abort();
+        }
+        if let Some(pid) = pid {
+            self.processes.kill(pid);
+        }
+        job_id
+    }
+
+    fn invalidate_cache(&mut self) {
+        self.cache.clear();
+        self.cache_epoch += 1;
+        self.prefetch_generation
+            .fetch_add(1, atomic::Ordering::Relaxed);
+        self.prefetching.clear();
+    }
+
+    /// Compute the previews of the given entries in the background for
+    /// them to land in the cache, dropping any earlier prefetch that
+    /// hasn't started yet.
+    ///
+    /// At most `prefetch_workers` previews are computed at once, and
+    /// entries that are already cached or being prefetched are skipped.
+    fn prefetch(&mut self, entries: Vec<Entry>) {
+        let generation = self
+            .prefetch_generation
+            .fetch_add(1, atomic::Ordering::Relaxed)
+            + 1;
+        if self.config.cache_size == 0 || self.config.prefetch_workers == 0 {
+            return;
+        }
+        for entry in entries {
+            let command =
+                self.preview_command.format_with_size(&entry, self.size);
+            if self.cache.contains(&command)
+                || !self.prefetching.insert(command.clone())
+            {
+                continue;
+            }
+            let job = PreviewJob {
+                delay: Duration::ZERO,
+                command,
+                entry,
+                processes: self.processes.clone(),
+                job_timeout: self.config.job_timeout,
+                max_lines: self.config.max_lines,
+                size: self.size,
+                pty: self.config.pty,
+            };
+            let cache_epoch = self.cache_epoch;
+            let current_generation = self.prefetch_generation.clone();
+            let slots = self.prefetch_slots.clone();
+            let finished = self.finished_tx.clone();
+            tokio::spawn(async move {
+                let preview = match slots.acquire().await {
+                    Ok(_permit)
+                        if current_generation
+                            .load(atomic::Ordering::Relaxed)
+                            == generation =>
+                    {
+                        job.run(None).await
+                    }
+                    // outdated before it even started
+                    _ => None,
+                };
+                let _ = finished.send(Finished {
+                    command: job.command,
+                    cache_epoch,
+                    preview,
+                });
+            });
+        }
+    }
+}
+
+/// The job whose output is currently streamed to the UI.
+#[derive(Debug, Default)]
+struct CurrentJob {
+    id: u64,
+    /// The process the job is running, to be killed if the job is stopped.
+    pid: Option<u32>,
 }
 
-pub fn try_preview(
-    command: &str,
-    entry: &Entry,
-    results_handle: &UnboundedSender<Preview>,
-) {
-    debug!("Preview command: {}", command);
+/// Streams the output of a preview job to the UI as it comes.
+struct StreamSink {
+    results: UnboundedSender<Update>,
+    job_id: u64,
+    current_job: Arc<Mutex<CurrentJob>>,
+    /// Whether the preview was sent over already, in which case more
+    /// output is appended to it.
+    started: bool,
+}
 
-    let child = shell_command(false)
-        .arg(command)
-        .output()
-        .expect("failed to execute process");
+impl StreamSink {
+    /// Record the process the job is running, returning whether the job is
+    /// still the current one.
+    fn track(&self, pid: u32) -> bool {
+        let mut current_job = self.current_job.lock();
+        if current_job.id == self.job_id {
+            current_job.pid = Some(pid);
+            true
+        } else {
+            false
+        }
+    }
 
-    let preview: Preview = {
-        if child.status.success() {
-            let (content, _) = replace_non_printable(
-                &child.stdout,
-                ReplaceNonPrintableConfig::default()
-                    .keep_line_feed()
-                    .keep_control_characters(),
-            );
-            Preview::new(
-                &entry.name,
-                content.to_string(),
-                None,
-                u16::try_from(content.lines().count()).unwrap_or(u16::MAX),
-            )
+    /// Send the given preview over, unless the job was superseded.
+    fn send_preview(&mut self, preview: Preview) {
+        let current_job = self.current_job.lock();
+        if current_job.id == self.job_id {
+            self.started = true;
+            let _ = self.results.send(Update::Preview(preview));
+        }
+    }
+
+    /// Send more output over, unless the job was superseded.
+    fn send_chunk(&mut self, title: &str, chunk: String) {
+        if self.started {
+            if chunk.is_empty() {
+                return;
+            }
+            let current_job = self.current_job.lock();
+            if current_job.id == self.job_id {
+                let _ = self.results.send(Update::Append(chunk));
+            }
+        } else {
+            let total_lines = line_count(&chunk);
+            self.send_preview(Preview::new(title, chunk, None, total_lines));
+        }
+    }
+
+    /// Report that the command timed out, unless the job was superseded.
+    fn send_timeout(&mut self, title: &str) {
+        if self.started {
+            let current_job = self.current_job.lock();
+            if current_job.id == self.job_id {
+                let _ = self.results.send(Update::TimedOut);
+            }
         } else {
-            let (content, _) = replace_non_printable(
-                &child.stderr,
+            self.send_preview(Preview::timed_out(title));
+        }
+    }
+}
+
+/// How often the output of a preview command is sent over to the UI while
+/// it's running.
+const STREAM_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A run of the preview command for a given entry.
+struct PreviewJob {
+    /// How long to wait before running the command.
+    delay: Duration,
+    command: String,
+    entry: Entry,
+    processes: ProcessTracker,
+    job_timeout: Duration,
+    max_lines: usize,
+    size: PreviewSize,
+    pty: bool,
+}
+
+impl PreviewJob {
+    /// Run the preview command, streaming its output to `sink` if there's
+    /// one.
+    ///
+    /// The command is killed once it produced `max_lines` lines, or if it
+    /// goes `job_timeout` without producing any output. Returns the whole
+    /// preview if the command ran to completion, `None` otherwise.
+    async fn run(&self, mut sink: Option<StreamSink>) -> Option<Preview> {
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        debug!("Preview command: {}", self.command);
+        let title = &self.entry.name;
+
+        let pty = if self.pty {
+            pty::open(self.size)
+                .inspect_err(|e| debug!("Failed to open a pty: {:?}", e))
+                .ok()
+        } else {
+            None
+        };
+        let (mut child, master) = match self.spawn(pty) {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                debug!("Failed to run preview command: {:?}", e);
+                let preview = Preview::error(
+                    title,
+                    format!("Failed to run preview command: {e}"),
+                );
+                if let Some(sink) = &mut sink {
+                    sink.send_preview(preview.clone());
+                }
+                return Some(preview);
+            }
+        };
+        // a child only goes without a pid once it was reaped, in which case
+        // there's nothing to track or kill
+        let pid = child.id();
+        if let Some(pid) = pid {
+            self.processes.register(pid);
+            // the job may have been stopped while starting the command
+            if sink.as_ref().is_some_and(|sink| !sink.track(pid)) {
+                self.processes.kill(pid);
+            }
+        }
+        let kill = || {
+            if let Some(pid) = pid {
+                kill_process_group(pid);
+            }
+        };
+        // in a pty, the error output is mixed in with the rest
+        let (output, stderr): (Pin<Box<dyn AsyncRead + Send>>, _) =
+            if let Some(master) = master {
+                (Box::pin(tokio::fs::File::from_std(master)), None)
+            } else {
+                let mut stderr = child.stderr.take().unwrap();
+                (
+                    Box::pin(child.stdout.take().unwrap()),
+                    Some(tokio::spawn(async move {
+                        let mut output = Vec::new();
+                        let _ = stderr.read_to_end(&mut output).await;
+                        output
+                    })),
+                )
+            };
+        let in_pty = stderr.is_none();
+        let mut stdout = BufReader::new(output);
+
+        let mut content = String::new();
+        let mut lines = 0;
+        let mut pending = String::new();
+        let mut last_sent = Instant::now();
+        let mut line = Vec::new();
+        let outcome = loop {
+            line.clear();
+            match timeout(
+                self.job_timeout,
+                stdout.read_until(b'\n', &mut line),
+            )
+            .await
+            {
+                Ok(Ok(0)) => break Outcome::Complete,
+                Ok(Err(e)) if in_pty && pty::is_closed(&e) => {
+                    break Outcome::Complete;
+                }
+                Ok(Ok(_)) => {
+                    // terminals end lines with `\r\n`
+                    if in_pty && line.ends_with(b"\r\n") {
+                        line.truncate(line.len() - 2);
+                        line.push(b'\n');
+                    }
+                    let (printable, _) = replace_non_printable(
+                        &line,
+                        ReplaceNonPrintableConfig::default()
+                            .keep_line_feed()
+                            .keep_control_characters(),
+                    );
+                    pending.push_str(&printable);
+                    lines += 1;
+                    if lines >= self.max_lines {
+                        debug!("Preview reached {} lines, stopping", lines);
+                        kill();
+                        break Outcome::Complete;
+                    }
+                    // send whatever is there whenever the command takes a
+                    // break, at a bounded rate otherwise
+                    if let Some(sink) = &mut sink {
+                        if stdout.buffer().is_empty()
+                            || last_sent.elapsed() >= STREAM_INTERVAL
+                        {
+                            content.push_str(&pending);
+                            sink.send_chunk(
+                                title,
+                                std::mem::take(&mut pending),
+                            );
+                            last_sent = Instant::now();
+                        }
+                    }
+                }
+                Ok(Err(e)) => {
+                    debug!("Failed to read preview output: {:?}", e);
+                    break Outcome::Failed;
+                }
+                Err(_) => {
+                    debug!("Preview job timeout: {}", self.command);
+                    kill();
+                    break Outcome::TimedOut;
+                }
+            }
+        };
+        content.push_str(&pending);
+
+        // the command might keep running after closing its output
+        let status = timeout(self.job_timeout, child.wait()).await.ok();
+        if status.is_none() {
+            debug!("Preview job timeout: {}", self.command);
+            kill();
+        }
+        if pid.is_some_and(|pid| !self.processes.unregister(pid)) {
+            debug!(
+                "Preview process {:?} was killed, discarding its output",
+                pid
+            );
+            return None;
+        }
+        let Some(status) = status.filter(|_| outcome == Outcome::Complete)
+        else {
+            if let Some(sink) = &mut sink {
+                if !pending.is_empty() {
+                    sink.send_chunk(title, pending);
+                }
+                if outcome != Outcome::Failed {
+                    sink.send_timeout(title);
+                }
+            }
+            return None;
+        };
+        let status = match status {
+            Ok(status) => status,
+            Err(e) => {
+                debug!("Failed to wait on preview command: {:?}", e);
+                let preview = Preview::error(
+                    title,
+                    format!("Failed to wait on preview command: {e}"),
+                );
+                if let Some(sink) = &mut sink {
+                    sink.send_preview(preview.clone());
+                }
+                return Some(preview);
+            }
+        };
+
+        // commands that fail without any output are previewed with their
+        // error output instead
+        if let Some(stderr) =
+            stderr.filter(|_| !status.success() && content.is_empty())
+        {
+            let (printable, _) = replace_non_printable(
+                &stderr.await.unwrap_or_default(),
                 ReplaceNonPrintableConfig::default()
                     .keep_line_feed()
                     .keep_control_characters(),
             );
-            Preview::new(
-                &entry.name,
-                content.to_string(),
-                None,
-                u16::try_from(content.lines().count()).unwrap_or(u16::MAX),
-            )
+            content = printable.to_string();
+        }
+        let preview =
+            Preview::new(title, content.clone(), None, line_count(&content));
+        if let Some(sink) = &mut sink {
+            if sink.started {
+                if !pending.is_empty() {
+                    sink.send_chunk(title, pending);
+                }
+            } else {
+                sink.send_preview(preview.clone());
+            }
         }
-    };
-    results_handle
-        .send(preview)
-        .expect("Unable to send preview result to main thread.");
+        Some(preview)
+    }
+}
+
+impl PreviewJob {
+    /// Spawn the preview command, with its output going to the given pty
+    /// if there's one and piped otherwise, returning the master end of the
+    /// pty along with the child.
+    fn spawn(
+        &self,
+        pty: Option<pty::Pty>,
+    ) -> io::Result<(Child, Option<File>)> {
+        let mut command = tokio::process::Command::from(shell_command(false));
+        command
+            .arg(&self.command)
+            .env(PREVIEW_COLUMNS_ENV, self.size.columns.to_string())
+            .env(PREVIEW_LINES_ENV, self.size.lines.to_string())
+            .kill_on_drop(true);
+        let master = if let Some(pty) = pty {
+            command
+                .stdin(Stdio::null())
+                .stdout(pty.slave.try_clone()?)
+                .stderr(pty.slave)
+                // there's nobody to scroll through a pager
+                .env("PAGER", "cat")
+                .env("GIT_PAGER", "cat");
+            Some(pty.master)
+        } else {
+            command.stdout(Stdio::piped()).stderr(Stdio::piped());
+            None
+        };
+        // `command` holds on to the slave end of the pty until it's
+        // dropped, which needs to happen for the output to ever end
+        command.spawn().map(|child| (child, master))
+    }
+}
+
+/// How the output of a preview command was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Complete,
+    TimedOut,
+    Failed,
+}
+
+fn line_count(content: &str) -> u16 {
+    u16::try_from(content.lines().count()).unwrap_or(u16::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_count(path: &std::path::Path) -> usize {
+        std::fs::read_to_string(path).map_or(0, |s| s.lines().count())
+    }
+
+    fn spawn_previewer(
+        command: &str,
+        config: Config,
+    ) -> (UnboundedSender<Request>, UnboundedReceiver<Update>) {
+        let (requests_tx, requests_rx) = unbounded_channel();
+        let (results_tx, results_rx) = unbounded_channel();
+        let previewer = Previewer::new(
+            PreviewCommand::new(command, ":", None),
+            config,
+            requests_rx,
+            results_tx,
+            ProcessTracker::default(),
+        );
+        tokio::spawn(previewer.run());
+        (requests_tx, results_rx)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_preview_is_streamed() {
+        let (requests_tx, mut results_rx) = spawn_previewer(
+            "echo {}; sleep 0.2; echo second",
+            Config::default(),
+        );
+        requests_tx
+            .send(Request::Preview(Ticket::new(Entry::new("first".into()))))
+            .unwrap();
+
+        let Some(Update::Preview(mut preview)) = results_rx.recv().await
+        else {
+            panic!("expected a preview");
+        };
+        assert_eq!(preview.title, "first");
+        assert_eq!(preview.content, "first\n");
+        let Some(Update::Append(chunk)) = results_rx.recv().await else {
+            panic!("expected more output");
+        };
+        preview.append(&chunk);
+        assert_eq!(preview.content, "first\nsecond\n");
+        assert_eq!(preview.total_lines, 2);
+        requests_tx.send(Request::Shutdown).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_preview_is_bounded() {
+        let (requests_tx, mut results_rx) =
+            spawn_previewer("yes {}", Config::default().max_lines(10));
+        requests_tx
+            .send(Request::Preview(Ticket::new(Entry::new("y".into()))))
+            .unwrap();
+
+        let Some(Update::Preview(mut preview)) = results_rx.recv().await
+        else {
+            panic!("expected a preview");
+        };
+        // the command never stops on its own
+        while let Ok(Some(Update::Append(chunk))) =
+            timeout(Duration::from_millis(200), results_rx.recv()).await
+        {
+            preview.append(&chunk);
+        }
+        assert_eq!(preview.total_lines, 10);
+        requests_tx.send(Request::Shutdown).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_preview_times_out() {
+        let config = Config::default().job_timeout(Duration::from_millis(100));
+        let (requests_tx, mut results_rx) =
+            spawn_previewer("echo {}; sleep 5", config.clone());
+        requests_tx
+            .send(Request::Preview(Ticket::new(Entry::new("a".into()))))
+            .unwrap();
+
+        let Some(Update::Preview(preview)) = results_rx.recv().await else {
+            panic!("expected a preview");
+        };
+        assert_eq!(preview.content, "a\n");
+        assert!(!preview.timed_out);
+        assert!(matches!(results_rx.recv().await, Some(Update::TimedOut)));
+
+        // a command that doesn't output anything still gets a preview
+        let (requests_tx, mut results_rx) = spawn_previewer("sleep 5", config);
+        requests_tx
+            .send(Request::Preview(Ticket::new(Entry::new("b".into()))))
+            .unwrap();
+        let Some(Update::Preview(preview)) = results_rx.recv().await else {
+            panic!("expected a preview");
+        };
+        assert_eq!(preview.title, "b");
+        assert!(preview.timed_out);
+        requests_tx.send(Request::Shutdown).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_preview_size() {
+        let command =
+            "[ -t 1 ] && echo tty || echo pipe; echo $TV_PREVIEW_COLUMNS {lines}";
+        for (pty, expected) in
+            [(false, "pipe\n100 30\n"), (true, "tty\n100 30\n")]
+        {
+            let (requests_tx, mut results_rx) =
+                spawn_previewer(command, Config::default().pty(pty));
+            requests_tx
+                .send(Request::Resize(PreviewSize {
+                    columns: 100,
+                    lines: 30,
+                }))
+                .unwrap();
+            requests_tx
+                .send(Request::Preview(Ticket::new(Entry::new("a".into()))))
+                .unwrap();
+
+            let Some(Update::Preview(mut preview)) = results_rx.recv().await
+            else {
+                panic!("expected a preview");
+            };
+            while let Ok(Some(Update::Append(chunk))) =
+                timeout(Duration::from_millis(200), results_rx.recv()).await
+            {
+                preview.append(&chunk);
+            }
+            assert_eq!(preview.content, expected);
+            requests_tx.send(Request::Shutdown).unwrap();
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_stopping_a_job_leaves_prefetches_running() {
+        let processes = ProcessTracker::default();
+        let (requests_tx, requests_rx) = unbounded_channel();
+        let (results_tx, _results_rx) = unbounded_channel();
+        let previewer = Previewer::new(
+            PreviewCommand::new("sleep 30 # {}", ":", None),
+            Config::default(),
+            requests_rx,
+            results_tx,
+            processes.clone(),
+        );
+        let handle = tokio::spawn(previewer.run());
+        let wait_for = |count: usize| {
+            let processes = processes.clone();
+            async move {
+                let start = Instant::now();
+                while processes.len() != count
+                    && start.elapsed() < Duration::from_secs(5)
+                {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                processes.len()
+            }
+        };
+
+        requests_tx
+            .send(Request::Prefetch(vec![Entry::new("a".to_string())]))
+            .unwrap();
+        requests_tx
+            .send(Request::Preview(Ticket::new(Entry::new("b".to_string()))))
+            .unwrap();
+        assert_eq!(wait_for(2).await, 2);
+        // only the job streaming its preview is replaced
+        requests_tx
+            .send(Request::Preview(Ticket::new(Entry::new("c".to_string()))))
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(wait_for(2).await, 2);
+
+        requests_tx.send(Request::Shutdown).unwrap();
+        handle.await.unwrap();
+        assert!(processes.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_prefetched_previews_land_in_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+        // every run of the preview command is logged
+        let preview_command = PreviewCommand::new(
+            &format!("echo {{}} >> '{}'; echo {{}}", log.display()),
+            ":",
+            None,
+        );
+        let (requests_tx, requests_rx) = unbounded_channel();
+        let (results_tx, mut results_rx) = unbounded_channel();
+        let previewer = Previewer::new(
+            preview_command,
+            Config::default(),
+            requests_rx,
+            results_tx,
+            ProcessTracker::default(),
+        );
+        let handle = tokio::spawn(previewer.run());
+
+        requests_tx
+            .send(Request::Prefetch(vec![
+                Entry::new("a".to_string()),
+                Entry::new("b".to_string()),
+            ]))
+            .unwrap();
+        let start = Instant::now();
+        while line_count(&log) < 2 && start.elapsed() < Duration::from_secs(5)
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // leave some time for the prefetched previews to be cached
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        requests_tx
+            .send(Request::Preview(Ticket::new(Entry::new("b".to_string()))))
+            .unwrap();
+        let Some(Update::Preview(preview)) = results_rx.recv().await else {
+            panic!("expected a preview");
+        };
+        assert_eq!(preview.content, "b\n");
+        // the preview was served from the cache
+        assert_eq!(line_count(&log), 2);
+
+        requests_tx.send(Request::Shutdown).unwrap();
+        handle.await.unwrap();
+    }
 }
@@ -0,0 +1,152 @@
+//! Pseudo-terminals for preview commands to run in, so that they behave as
+//! they would in a terminal of the size of the preview (e.g. with colors).
+use std::{fs::File, io};
+
+use crate::previewer::PreviewSize;
+
+/// Both ends of a pseudo-terminal.
+///
+/// The command's output is written to `slave` and read from `master`.
+pub struct Pty {
+    pub master: File,
+    pub slave: File,
+}
+
+/// Open a pseudo-terminal of the given size.
+///
+/// Both ends are close-on-exec from the start: commands spawned meanwhile
+/// would otherwise inherit the slave end and keep it open, so that the
+/// output of the preview command would never end.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+pub fn open(size: PreviewSize) -> io::Result<Pty> {
+    use std::{
+        ffi::{CStr, OsStr},
+        fs::OpenOptions,
+        os::{
+            fd::FromRawFd,
+            unix::{ffi::OsStrExt, fs::OpenOptionsExt},
+        },
+    };
+
+    // SAFETY: `posix_openpt` doesn't have any precondition.
+    let fd = unsafe {
+        libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY | libc::O_CLOEXEC)
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `posix_openpt` just opened the file descriptor and nothing
+    // else owns it.
+    let master = unsafe { File::from_raw_fd(fd) };
+    // SAFETY: `fd` is a valid file descriptor owned by `master`.
+    if unsafe { libc::grantpt(fd) } != 0 || unsafe { libc::unlockpt(fd) } != 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    let mut name = [0u8; 128];
+    // SAFETY: `name` is valid for writes of its length and `fd` is a valid
+    // file descriptor owned by `master`.
+    let res =
+        unsafe { libc::ptsname_r(fd, name.as_mut_ptr().cast(), name.len()) };
+    if res != 0 {
+        return Err(io::Error::from_raw_os_error(res));
+    }
+    let name = CStr::from_bytes_until_nul(&name)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    // files are always opened with `O_CLOEXEC` by the standard library
+    let slave = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NOCTTY)
+        .open(OsStr::from_bytes(name.to_bytes()))?;
+
+    let winsize = libc::winsize {
+        ws_row: size.lines,
+        ws_col: size.columns,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: `fd` is a valid file descriptor owned by `master` and
+    // `TIOCSWINSZ` only reads `winsize`.
+    if unsafe { libc::ioctl(fd, libc::TIOCSWINSZ, &winsize) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(Pty { master, slave })
+}
+
+/// Open a pseudo-terminal of the given size.
+///
+/// `posix_openpt` doesn't take `O_CLOEXEC` on these platforms, so both ends
+/// are only made close-on-exec right after being opened.
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd"
+    ))
+))]
+pub fn open(size: PreviewSize) -> io::Result<Pty> {
+    use std::os::fd::FromRawFd;
+
+    let mut master = 0;
+    let mut slave = 0;
+    let mut winsize = libc::winsize {
+        ws_row: size.lines,
+        ws_col: size.columns,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: the pointers are valid for the duration of the call and
+    // `openpty` doesn't keep them around.
+    let res = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            // `*mut` on some platforms
+            std::ptr::addr_of_mut!(winsize),
+        )
+    };
+    if res != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `openpty` just opened both file descriptors and nothing else
+    // owns them.
+    let pty = unsafe {
+        Pty {
+            master: File::from_raw_fd(master),
+            slave: File::from_raw_fd(slave),
+        }
+    };
+    // the preview command only gets the slave as its output, which it
+    // receives as a copy that doesn't have this flag
+    for fd in [master, slave] {
+        // SAFETY: `fd` is a valid file descriptor owned by `pty`.
+        if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(pty)
+}
+
+/// Pseudo-terminals aren't supported on this platform.
+#[cfg(not(unix))]
+pub fn open(_size: PreviewSize) -> io::Result<Pty> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Whether reading from the master end of a pseudo-terminal failed because
+/// the slave end was closed, i.e. the command is done.
+pub fn is_closed(error: &io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        error.raw_os_error() == Some(libc::EIO)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = error;
+        false
+    }
+}
@@ -51,11 +51,19 @@ impl PreviewState {
         scroll: u16,
         target_line: Option<u16>,
     ) {
-        if self.preview.title != preview.title || self.scroll != scroll {
-            self.preview = preview;
-            self.scroll = scroll;
-            self.target_line = target_line;
-        }
+        self.preview = preview;
+        self.scroll = scroll;
+        self.target_line = target_line;
+    }
+
+    /// Add more lines to the preview, as they're streamed from its command.
+    pub fn append(&mut self, chunk: &str) {
+        self.preview.append(chunk);
+    }
+
+    /// Mark the preview as timed out.
+    pub fn time_out(&mut self) {
+        self.preview.timed_out = true;
     }
 
     pub fn for_render_context(&self) -> Self {
@@ -86,12 +94,14 @@ impl PreviewState {
 
         PreviewState::new(
             self.enabled,
-            Preview::new(
-                &self.preview.title,
-                cropped_content,
-                self.preview.icon,
-                self.preview.total_lines,
-            ),
+            Preview {
+                title: self.preview.title.clone(),
+                content: cropped_content,
+                icon: self.preview.icon,
+                total_lines: self.preview.total_lines,
+                error: self.preview.error.clone(),
+                timed_out: self.preview.timed_out,
+            },
             num_skipped_lines,
             target_line,
         )
@@ -14,6 +14,8 @@ pub struct Colorscheme {
 pub struct GeneralColorscheme {
     pub border_fg: Color,
     pub background: Option<Color>,
+    pub error_fg: Color,
+    pub warning_fg: Color,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -51,4 +53,5 @@ pub struct InputColorscheme {
 pub struct ModeColorscheme {
     pub channel: Color,
     pub remote_control: Color,
+    pub send_to_channel: Color,
 }
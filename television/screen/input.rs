@@ -1,4 +1,4 @@
-use crate::utils::input::Input;
+use crate::{television::MatchingMode, utils::input::Input};
 use anyhow::Result;
 use ratatui::{
     layout::{
@@ -25,6 +25,8 @@ pub fn draw_input_box(
     input_state: &Input,
     results_picker_state: &ListState,
     matcher_running: bool,
+    matching_mode: MatchingMode,
+    invalid_pattern: bool,
     channel_name: &str,
     spinner: &Spinner,
     colorscheme: &Colorscheme,
@@ -57,7 +59,14 @@ pub fn draw_input_box(
 
     f.render_widget(input_block, rect);
 
-    // split input block into 4 parts: prompt symbol, input, result count, spinner
+    let matching_mode_label = if invalid_pattern {
+        format!(" {matching_mode} (invalid) ")
+    } else {
+        format!(" {matching_mode} ")
+    };
+
+    // split input block into 5 parts: prompt symbol, input, matching mode,
+    // result count, spinner
     let inner_input_chunks = RatatuiLayout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -65,6 +74,8 @@ pub fn draw_input_box(
             Constraint::Length(2),
             // input field
             Constraint::Fill(1),
+            // matching mode
+            Constraint::Length(u16::try_from(matching_mode_label.len())?),
             // result count
             Constraint::Length(
                 3 * (u16::try_from((total_count.max(1)).ilog10()).unwrap()
@@ -101,9 +112,16 @@ pub fn draw_input_box(
     f.render_widget(input, inner_input_chunks[1]);
 
     if matcher_running {
-        f.render_widget(spinner, inner_input_chunks[3]);
+        f.render_widget(spinner, inner_input_chunks[4]);
     }
 
+    let matching_mode_paragraph = Paragraph::new(Span::styled(
+        matching_mode_label,
+        Style::default().fg(colorscheme.input.results_count_fg),
+    ))
+    .alignment(Alignment::Right);
+    f.render_widget(matching_mode_paragraph, inner_input_chunks[2]);
+
     let result_count_block = Block::default();
     let result_count_paragraph = Paragraph::new(Span::styled(
         format!(
@@ -121,7 +139,7 @@ pub fn draw_input_box(
     ))
     .block(result_count_block)
     .alignment(Alignment::Right);
-    f.render_widget(result_count_paragraph, inner_input_chunks[2]);
+    f.render_widget(result_count_paragraph, inner_input_chunks[3]);
 
     // Make the cursor visible and ask tui-rs to put it at the
     // specified coordinates after rendering
@@ -94,12 +94,41 @@ impl KeyBindings {
             ),
         ]);
 
+        // send to channel mode keybindings
+        let send_to_channel_bindings: FxHashMap<
+            DisplayableAction,
+            Vec<String>,
+        > = FxHashMap::from_iter(vec![
+            (
+                DisplayableAction::ResultsNavigation,
+                serialized_keys_for_actions(
+                    self,
+                    &[Action::SelectPrevEntry, Action::SelectNextEntry],
+                ),
+            ),
+            (
+                DisplayableAction::SelectEntry,
+                serialized_keys_for_actions(self, &[Action::ConfirmSelection]),
+            ),
+            (
+                DisplayableAction::ToggleSendToChannel,
+                serialized_keys_for_actions(
+                    self,
+                    &[Action::ToggleSendToChannel],
+                ),
+            ),
+        ]);
+
         FxHashMap::from_iter(vec![
             (Mode::Channel, DisplayableKeybindings::new(channel_bindings)),
             (
                 Mode::RemoteControl,
                 DisplayableKeybindings::new(remote_control_bindings),
             ),
+            (
+                Mode::SendToChannel,
+                DisplayableKeybindings::new(send_to_channel_bindings),
+            ),
         ])
     }
 }
@@ -132,6 +161,7 @@ pub enum DisplayableAction {
     SelectEntry,
     CopyEntryToClipboard,
     ToggleRemoteControl,
+    ToggleSendToChannel,
     Cancel,
     Quit,
     ToggleHelpBar,
@@ -147,6 +177,7 @@ impl Display for DisplayableAction {
                 "Copy entry to clipboard"
             }
             DisplayableAction::ToggleRemoteControl => "Toggle Remote control",
+            DisplayableAction::ToggleSendToChannel => "Toggle Send to channel",
             DisplayableAction::Cancel => "Cancel",
             DisplayableAction::Quit => "Quit",
             DisplayableAction::ToggleHelpBar => "Toggle help bar",
@@ -157,16 +188,26 @@ impl Display for DisplayableAction {
 
 pub fn build_keybindings_table<'a>(
     keybindings: &'a FxHashMap<Mode, DisplayableKeybindings>,
+    channel_actions: &'a [(String, String)],
     mode: Mode,
     colorscheme: &'a Colorscheme,
 ) -> Table<'a> {
     match mode {
         Mode::Channel => build_keybindings_table_for_channel(
             &keybindings[&mode],
+            channel_actions,
             colorscheme,
         ),
         Mode::RemoteControl => build_keybindings_table_for_channel_selection(
             &keybindings[&mode],
+            &DisplayableAction::ToggleRemoteControl,
+            colorscheme.mode.remote_control,
+            colorscheme,
+        ),
+        Mode::SendToChannel => build_keybindings_table_for_channel_selection(
+            &keybindings[&mode],
+            &DisplayableAction::ToggleSendToChannel,
+            colorscheme.mode.send_to_channel,
             colorscheme,
         ),
     }
@@ -174,6 +215,7 @@ pub fn build_keybindings_table<'a>(
 
 fn build_keybindings_table_for_channel<'a>(
     keybindings: &'a DisplayableKeybindings,
+    channel_actions: &'a [(String, String)],
     colorscheme: &'a Colorscheme,
 ) -> Table<'a> {
     // Results navigation
@@ -236,6 +278,16 @@ fn build_keybindings_table_for_channel<'a>(
         colorscheme.mode.channel,
     ));
 
+    // Channel specific actions
+    let channel_action_rows = channel_actions.iter().map(|(name, key)| {
+        Row::new(build_cells_for_group(
+            name,
+            std::slice::from_ref(key),
+            colorscheme.help.metadata_field_name_fg,
+            colorscheme.mode.channel,
+        ))
+    });
+
     let widths = vec![Constraint::Fill(1), Constraint::Fill(2)];
 
     Table::new(
@@ -245,13 +297,17 @@ fn build_keybindings_table_for_channel<'a>(
             select_entry_row,
             copy_entry_row,
             switch_channels_row,
-        ],
+        ]
+        .into_iter()
+        .chain(channel_action_rows),
         widths,
     )
 }
 
 fn build_keybindings_table_for_channel_selection<'a>(
     keybindings: &'a DisplayableKeybindings,
+    toggle_action: &DisplayableAction,
+    mode_color: Color,
     colorscheme: &'a Colorscheme,
 ) -> Table<'a> {
     // Results navigation
@@ -263,7 +319,7 @@ fn build_keybindings_table_for_channel_selection<'a>(
         "Browse channels",
         navigation_keys,
         colorscheme.help.metadata_field_name_fg,
-        colorscheme.mode.remote_control,
+        mode_color,
     ));
 
     // Select entry
@@ -275,19 +331,17 @@ fn build_keybindings_table_for_channel_selection<'a>(
         "Select channel",
         select_entry_keys,
         colorscheme.help.metadata_field_name_fg,
-        colorscheme.mode.remote_control,
+        mode_color,
     ));
 
-    // Remote control
-    let switch_channels_keys = keybindings
-        .bindings
-        .get(&DisplayableAction::ToggleRemoteControl)
-        .unwrap();
+    // Remote control or send to channel
+    let switch_channels_keys =
+        keybindings.bindings.get(toggle_action).unwrap();
     let switch_channels_row = Row::new(build_cells_for_group(
-        "Toggle Remote control",
+        &toggle_action.to_string(),
         switch_channels_keys,
         colorscheme.help.metadata_field_name_fg,
-        colorscheme.mode.remote_control,
+        mode_color,
     ));
 
     Table::new(
@@ -15,6 +15,7 @@ impl Display for Mode {
         match self {
             Mode::Channel => write!(f, "Channel"),
             Mode::RemoteControl => write!(f, "Remote Control"),
+            Mode::SendToChannel => write!(f, "Send to Channel"),
         }
     }
 }
@@ -7,6 +7,7 @@ pub mod layout;
 pub mod logo;
 pub mod metadata;
 pub mod mode;
+pub mod notifications;
 pub mod preview;
 pub mod remote_control;
 pub mod results;
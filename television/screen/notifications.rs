@@ -0,0 +1,258 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::screen::colors::Colorscheme;
+
+/// How bad whatever a notification reports is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// How long transient notifications stay on screen.
+pub const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A message shown to the user on top of the rest of the UI.
+///
+/// Notifications are either transient, going away on their own after a
+/// while, or sticky, staying on screen until they're dismissed. Errors are
+/// sticky by default while other notifications are transient.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+    /// When the notification should go away on its own, if ever.
+    expires_at: Option<Instant>,
+}
+
+impl Notification {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        let notification = Self {
+            severity,
+            message: message.into(),
+            expires_at: None,
+        };
+        if severity == Severity::Error {
+            notification
+        } else {
+            notification.with_timeout(NOTIFICATION_TIMEOUT)
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(Severity::Info, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// Make the notification go away on its own after the given duration.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.expires_at = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// Keep the notification on screen until it's dismissed.
+    #[must_use]
+    pub fn sticky(mut self) -> Self {
+        self.expires_at = None;
+        self
+    }
+
+    pub fn is_sticky(&self) -> bool {
+        self.expires_at.is_none()
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// The maximum number of notifications kept at once.
+///
+/// Once this is reached, the oldest notifications are dropped.
+const MAX_NOTIFICATIONS: usize = 5;
+
+/// The notifications currently shown to the user, the most recent one last.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Notifications {
+    items: Vec<Notification>,
+}
+
+impl Notifications {
+    /// Show the given notification.
+    ///
+    /// A notification with the same severity and message as one already
+    /// shown replaces it rather than being stacked on top of it.
+    pub fn push(&mut self, notification: Notification) {
+        self.items.retain(|n| {
+            n.severity != notification.severity
+                || n.message != notification.message
+        });
+        self.items.push(notification);
+        if self.items.len() > MAX_NOTIFICATIONS {
+            self.items.drain(..self.items.len() - MAX_NOTIFICATIONS);
+        }
+    }
+
+    /// Dismiss the most recent notification, returning whether there was
+    /// one.
+    pub fn dismiss(&mut self) -> bool {
+        self.items.pop().is_some()
+    }
+
+    /// Drop the transient notifications that expired as of `now`, returning
+    /// whether there were any.
+    pub fn prune(&mut self, now: Instant) -> bool {
+        let len = self.items.len();
+        self.items.retain(|n| !n.is_expired(now));
+        self.items.len() != len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Notification> {
+        self.items.iter()
+    }
+}
+
+/// The maximum width of a notification, borders included.
+const MAX_NOTIFICATION_WIDTH: u16 = 60;
+
+/// Draw the given notifications stacked in the top right corner of `area`,
+/// the most recent one first.
+pub fn draw_notifications(
+    f: &mut Frame,
+    area: Rect,
+    notifications: &Notifications,
+    colorscheme: &Colorscheme,
+) {
+    let width = MAX_NOTIFICATION_WIDTH.min(area.width);
+    // borders
+    let text_width = usize::from(width.saturating_sub(2)).max(1);
+    let mut y = area.y;
+    for notification in notifications.iter().rev() {
+        let text_height = notification
+            .message
+            .lines()
+            .map(|line| line.chars().count().max(1).div_ceil(text_width))
+            .sum::<usize>()
+            .max(1);
+        let height = u16::try_from(text_height)
+            .unwrap_or(u16::MAX)
+            .saturating_add(2)
+            .min(area.bottom().saturating_sub(y));
+        if height < 3 {
+            break;
+        }
+        let rect = Rect::new(area.right() - width, y, width, height);
+        let color = severity_color(notification.severity, colorscheme);
+        let block = Block::default()
+            .title_top(
+                Line::from(format!(" {} ", notification.severity.label()))
+                    .alignment(Alignment::Left)
+                    .bold(),
+            )
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(color))
+            .style(
+                Style::default()
+                    .bg(colorscheme.general.background.unwrap_or_default()),
+            );
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Paragraph::new(notification.message.as_str())
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            rect,
+        );
+        y += height;
+    }
+}
+
+fn severity_color(severity: Severity, colorscheme: &Colorscheme) -> Color {
+    match severity {
+        Severity::Info => colorscheme.general.border_fg,
+        Severity::Warning => colorscheme.general.warning_fg,
+        Severity::Error => colorscheme.general.error_fg,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_are_sticky() {
+        assert!(Notification::error("boom").is_sticky());
+        assert!(!Notification::warning("careful").is_sticky());
+        assert!(Notification::info("fyi").sticky().is_sticky());
+    }
+
+    #[test]
+    fn test_push_replaces_identical_notifications() {
+        let mut notifications = Notifications::default();
+        notifications.push(Notification::error("boom"));
+        notifications.push(Notification::warning("careful"));
+        notifications.push(Notification::error("boom"));
+        assert_eq!(
+            notifications
+                .iter()
+                .map(|n| n.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["careful", "boom"]
+        );
+
+        for i in 0..MAX_NOTIFICATIONS {
+            notifications.push(Notification::info(i.to_string()));
+        }
+        assert_eq!(notifications.iter().count(), MAX_NOTIFICATIONS);
+        assert_eq!(notifications.iter().next().unwrap().message, "0");
+    }
+
+    #[test]
+    fn test_prune_and_dismiss() {
+        let mut notifications = Notifications::default();
+        notifications.push(Notification::error("boom"));
+        notifications.push(
+            Notification::warning("careful")
+                .with_timeout(Duration::from_secs(1)),
+        );
+
+        assert!(!notifications.prune(Instant::now()));
+        assert!(notifications.prune(Instant::now() + Duration::from_secs(2)));
+        assert_eq!(notifications.iter().count(), 1);
+
+        assert!(notifications.dismiss());
+        assert!(notifications.is_empty());
+        assert!(!notifications.dismiss());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::previewer::state::PreviewState;
+use crate::previewer::{state::PreviewState, PreviewSize};
 use crate::screen::colors::Colorscheme;
 use crate::utils::strings::{
     replace_non_printable, shrink_with_ellipsis, ReplaceNonPrintableConfig,
@@ -15,6 +15,20 @@ use ratatui::{
 };
 use std::str::FromStr;
 
+const TIMED_OUT_MESSAGE: &str = "Preview timed out";
+
+/// The size available to the content of a preview displayed in the given
+/// window, i.e. without the borders and padding around it.
+pub fn preview_size(preview_window: Rect) -> PreviewSize {
+    PreviewSize {
+        // borders, the outer block's right padding and the paragraph's
+        // padding on both sides
+        columns: preview_window.width.saturating_sub(5),
+        // borders and the outer block's top padding
+        lines: preview_window.height.saturating_sub(3),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn draw_preview_content_block(
     f: &mut Frame,
@@ -29,8 +43,19 @@ pub fn draw_preview_content_block(
         colorscheme,
         preview_state.preview.icon,
         &preview_state.preview.title,
+        preview_state.preview.timed_out,
         use_nerd_font_icons,
     )?;
+    if preview_state.preview.timed_out
+        && preview_state.preview.content.is_empty()
+    {
+        f.render_widget(
+            build_meta_preview_paragraph(inner, TIMED_OUT_MESSAGE, ' ')
+                .style(Style::default().fg(colorscheme.general.warning_fg)),
+            inner,
+        );
+        return Ok(());
+    }
     // render the preview content
     let rp = build_preview_paragraph(
         preview_state,
@@ -138,6 +163,7 @@ fn draw_content_outer_block(
     colorscheme: &Colorscheme,
     icon: Option<FileIcon>,
     title: &str,
+    timed_out: bool,
     use_nerd_font_icons: bool,
 ) -> Result<Rect> {
     let mut preview_title_spans = vec![Span::from(" ")];
@@ -168,7 +194,7 @@ fn draw_content_outer_block(
     preview_title_spans.push(Span::from(" "));
 
     // build the preview block
-    let preview_outer_block = Block::default()
+    let mut preview_outer_block = Block::default()
         .title_top(
             Line::from(preview_title_spans)
                 .alignment(Alignment::Center)
@@ -183,6 +209,15 @@ fn draw_content_outer_block(
         )
         .padding(Padding::new(0, 1, 1, 0));
 
+    // whatever was output before the command timed out is still shown
+    if timed_out {
+        preview_outer_block = preview_outer_block.title_bottom(
+            Line::from(" timed out ")
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(colorscheme.general.warning_fg)),
+        );
+    }
+
     let inner = preview_outer_block.inner(rect);
     f.render_widget(preview_outer_block, rect);
     Ok(inner)
@@ -46,7 +46,7 @@ pub fn draw_remote_control(
         picker_state,
         colorscheme,
     );
-    draw_rc_input(f, layout[1], input_state, colorscheme)?;
+    draw_rc_input(f, layout[1], input_state, *mode, colorscheme)?;
     draw_rc_logo(
         f,
         layout[2],
@@ -91,10 +91,11 @@ fn draw_rc_input(
     f: &mut Frame,
     area: Rect,
     input: &mut Input,
+    mode: Mode,
     colorscheme: &Colorscheme,
 ) -> Result<()> {
     let input_block = Block::default()
-        .title_top(Line::from("Remote Control").alignment(Alignment::Center))
+        .title_top(Line::from(mode.to_string()).alignment(Alignment::Center))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(colorscheme.general.border_fg))
@@ -1,7 +1,10 @@
-use crate::channels::entry::Entry;
+use crate::channels::{cable::SourceError, entry::Entry};
 use crate::screen::colors::{Colorscheme, ResultsColorscheme};
 use crate::screen::layout::InputPosition;
-use crate::utils::indices::truncate_highlighted_string;
+use crate::utils::ansi::{strip_ansi, StyledRange};
+use crate::utils::indices::{
+    truncate_highlighted_string, truncate_highlighted_string_with_skip,
+};
 use crate::utils::strings::make_matched_string_printable;
 use anyhow::Result;
 use ratatui::layout::{Alignment, Rect};
@@ -9,9 +12,11 @@ use ratatui::prelude::{Color, Line, Span, Style};
 use ratatui::style::Stylize;
 use ratatui::widgets::{
     Block, BorderType, Borders, List, ListDirection, ListState, Padding,
+    Paragraph, Wrap,
 };
 use ratatui::Frame;
 use rustc_hash::FxHashSet;
+use std::ops::Range;
 use std::str::FromStr;
 use unicode_width::UnicodeWidthStr;
 
@@ -42,8 +47,8 @@ fn max_widths(
     }
 
     // otherwise, use up the available space for both name and value as nicely as possible
-    let name_len =
-        u16::try_from(entry.name.chars().count()).unwrap_or(u16::MAX);
+    let name_len = u16::try_from(entry.display_name().chars().count())
+        .unwrap_or(u16::MAX);
     let value_len = entry
         .value
         .as_ref()
@@ -102,54 +107,81 @@ fn build_result_line<'a>(
     // entry name
     let (mut entry_name, mut name_match_ranges) =
         make_matched_string_printable(
-            &entry.name,
+            entry.display_name(),
             entry.name_match_ranges.as_deref(),
         );
+    let mut name_styles = printable_styles(entry);
     // if the name is too long, we need to truncate it and add an ellipsis
     if entry_name.as_str().width() > name_max_width as usize {
-        (entry_name, name_match_ranges) = truncate_highlighted_string(
-            &entry_name,
-            &name_match_ranges,
-            name_max_width,
-        );
+        let skipped;
+        (entry_name, name_match_ranges, skipped) =
+            truncate_highlighted_string_with_skip(
+                &entry_name,
+                &name_match_ranges,
+                name_max_width,
+            );
+        if let Some(skipped) = skipped {
+            // the skipped characters were replaced by an ellipsis
+            let skipped = u32::try_from(skipped).unwrap_or(u32::MAX);
+            name_styles = name_styles
+                .into_iter()
+                .map(|(start, end, style)| {
+                    (
+                        start.saturating_sub(skipped) + 1,
+                        end.saturating_sub(skipped) + 1,
+                        style,
+                    )
+                })
+                .filter(|(start, end, _)| start != end)
+                .collect();
+        }
     }
 
+    // the name's own styles (e.g. ANSI colors) are layered under the match
+    // highlighting
+    let name_style = |style: Option<Style>| {
+        let base = Style::default().fg(colorscheme.result_name_fg);
+        style.map_or(base, |style| base.patch(style))
+    };
+    let match_style = |style: Option<Style>| {
+        style
+            .unwrap_or_default()
+            .patch(Style::default().fg(colorscheme.match_foreground_color))
+    };
     let mut last_match_end = 0;
-    let name_chars = entry_name.chars();
     let name_len = entry_name.as_str().width();
     for (start, end) in name_match_ranges
         .iter()
         .map(|(s, e)| (*s as usize, *e as usize))
     {
         // from the end of the last match to the start of the current one
-        spans.push(Span::styled(
-            name_chars
-                .clone()
-                .skip(last_match_end)
-                .take(start - last_match_end)
-                .collect::<String>(),
-            //entry_name[last_match_end..start].to_string(),
-            Style::default().fg(colorscheme.result_name_fg),
-        ));
+        push_styled_spans(
+            &mut spans,
+            &entry_name,
+            last_match_end..start,
+            &name_styles,
+            name_style,
+        );
         // the current match
-        spans.push(Span::styled(
-            name_chars
-                .clone()
-                .skip(start)
-                .take(end - start)
-                .collect::<String>(),
-            Style::default().fg(colorscheme.match_foreground_color),
-        ));
+        push_styled_spans(
+            &mut spans,
+            &entry_name,
+            start..end,
+            &name_styles,
+            match_style,
+        );
         last_match_end = end;
     }
     // we need to push a span for the remainder of the entry name
     // but only if there's something left
     if last_match_end < name_len {
-        let remainder = name_chars.skip(last_match_end).collect::<String>();
-        spans.push(Span::styled(
-            remainder,
-            Style::default().fg(colorscheme.result_name_fg),
-        ));
+        push_styled_spans(
+            &mut spans,
+            &entry_name,
+            last_match_end..name_len,
+            &name_styles,
+            name_style,
+        );
     }
     // optional line number
     if let Some(line_number) = entry.line_number {
@@ -211,6 +243,63 @@ fn build_result_line<'a>(
     Line::from(spans)
 }
 
+/// The entry's name styles (see `Entry::name_styles`) translated the same
+/// way its match ranges are when making its name printable.
+fn printable_styles(entry: &Entry) -> Vec<StyledRange> {
+    let Some(styles) = &entry.name_styles else {
+        return Vec::new();
+    };
+    let ranges = styles
+        .iter()
+        .map(|(start, end, _)| (*start, *end))
+        .collect::<Vec<_>>();
+    let (_, ranges) =
+        make_matched_string_printable(entry.display_name(), Some(&ranges));
+    ranges
+        .into_iter()
+        .zip(styles)
+        .map(|((start, end), (_, _, style))| (start, end, *style))
+        .collect()
+}
+
+/// Push the given range of characters of `text` as spans, split according
+/// to the styled ranges of `text`.
+///
+/// `style` gives the style of each span from the style of the range it is
+/// part of, if any.
+fn push_styled_spans(
+    spans: &mut Vec<Span<'_>>,
+    text: &str,
+    range: Range<usize>,
+    styles: &[StyledRange],
+    style: impl Fn(Option<Style>) -> Style,
+) {
+    let mut position = range.start;
+    while position < range.end {
+        let styled = styles.iter().find(|(start, end, _)| {
+            (*start as usize..*end as usize).contains(&position)
+        });
+        let end = match styled {
+            Some((_, end, _)) => *end as usize,
+            None => styles
+                .iter()
+                .map(|(start, _, _)| *start as usize)
+                .filter(|start| *start > position)
+                .min()
+                .unwrap_or(range.end),
+        }
+        .min(range.end);
+        spans.push(Span::styled(
+            text.chars()
+                .skip(position)
+                .take(end - position)
+                .collect::<String>(),
+            style(styled.map(|(_, _, style)| *style)),
+        ));
+        position = end;
+    }
+}
+
 pub fn build_results_list<'a, 'b>(
     results_block: Block<'b>,
     entries: &'a [Entry],
@@ -254,6 +343,7 @@ pub fn draw_results_list(
     preview_keybinding: &str,
     preview_togglable: bool,
     no_help: bool,
+    source_error: Option<&SourceError>,
 ) -> Result<()> {
     let mut toggle_hints = String::new();
     if !no_help {
@@ -263,7 +353,7 @@ pub fn draw_results_list(
         toggle_hints.push_str(&format!(" preview: <{preview_keybinding}> ",));
     }
 
-    let results_block = Block::default()
+    let mut results_block = Block::default()
         .title_top(Line::from(" Results ").alignment(Alignment::Center))
         .title_bottom(Line::from(toggle_hints).alignment(Alignment::Center))
         .borders(Borders::ALL)
@@ -275,6 +365,26 @@ pub fn draw_results_list(
         )
         .padding(Padding::right(1));
 
+    if let Some(error) = source_error {
+        if entries.is_empty() {
+            // report the failure in place of the results
+            f.render_widget(
+                build_source_error_paragraph(
+                    results_block,
+                    error,
+                    colorscheme,
+                ),
+                rect,
+            );
+            return Ok(());
+        }
+        results_block = results_block.title_bottom(
+            Line::from(format!(" {error} "))
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(colorscheme.general.error_fg)),
+        );
+    }
+
     let results_list = build_results_list(
         results_block,
         entries,
@@ -292,6 +402,24 @@ pub fn draw_results_list(
     Ok(())
 }
 
+/// Build the paragraph reporting why the channel's source command failed,
+/// along with what it printed to its stderr.
+fn build_source_error_paragraph<'a>(
+    results_block: Block<'a>,
+    error: &SourceError,
+    colorscheme: &Colorscheme,
+) -> Paragraph<'a> {
+    let mut lines = vec![Line::from(error.to_string())
+        .style(Style::default().fg(colorscheme.general.error_fg).bold())];
+    lines.extend(error.stderr().lines().map(|line| {
+        Line::from(strip_ansi(line).replace('\t', "    "))
+            .style(Style::default().fg(colorscheme.results.result_name_fg))
+    }));
+    Paragraph::new(lines)
+        .block(results_block)
+        .wrap(Wrap { trim: false })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -347,4 +475,30 @@ mod tests {
 
         assert_eq!(result_line, expected_line);
     }
+
+    #[test]
+    fn test_build_result_line_with_styles() {
+        let red = Style::default().fg(Color::Red).bold();
+        let entry = Entry::new(String::from("red plain"))
+            .with_name_styles(vec![(0, 3, red)])
+            .with_name_match_indices(&[1, 4]);
+        let colorscheme = ResultsColorscheme {
+            match_foreground_color: Color::Yellow,
+            ..Default::default()
+        };
+        let result_line =
+            build_result_line(&entry, None, false, &colorscheme, 200);
+
+        // the name's colors are overridden by the match highlighting
+        let expected_line = Line::from(vec![
+            Span::raw("r").fg(Color::Red).bold(),
+            Span::raw("e").fg(Color::Yellow).bold(),
+            Span::raw("d").fg(Color::Red).bold(),
+            Span::raw(" ").fg(Color::Reset),
+            Span::raw("p").fg(Color::Yellow),
+            Span::raw("lain").fg(Color::Reset),
+        ]);
+
+        assert_eq!(result_line, expected_line);
+    }
 }
@@ -1,48 +1,143 @@
 use crate::{
     action::Action,
     channels::{
-        cable::Channel as CableChannel,
+        actions::ExecutionMode,
+        cable::{Channel as CableChannel, SourceError},
         entry::Entry,
         prototypes::{Cable, ChannelPrototype},
         remote_control::RemoteControl,
     },
     config::{Config, Theme},
     draw::{ChannelState, Ctx, TvState},
+    event::Key,
+    frecency::Frecency,
+    history::QueryHistory,
     input::convert_action_to_input_request,
     picker::Picker,
     previewer::{
-        state::PreviewState, Config as PreviewerConfig, Preview, Previewer,
-        Request as PreviewRequest, Ticket,
+        state::PreviewState, Preview, PreviewSize, Previewer,
+        Request as PreviewRequest, Ticket, Update as PreviewUpdate,
     },
     render::UiState,
     screen::{
         colors::Colorscheme,
         layout::InputPosition,
+        notifications::{Notification, Notifications},
+        preview::preview_size,
         spinner::{Spinner, SpinnerState},
     },
     utils::{
-        clipboard::CLIPBOARD, metadata::AppMetadata, strings::EMPTY_STRING,
+        clipboard::CLIPBOARD, input::Input, metadata::AppMetadata,
+        process::ProcessTracker, strings::EMPTY_STRING,
     },
 };
 use anyhow::Result;
 use rustc_hash::{FxBuildHasher, FxHashSet};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+    time::{Duration, Instant},
+};
 use tokio::sync::mpsc::{
     unbounded_channel, UnboundedReceiver, UnboundedSender,
 };
-use tracing::debug;
+use tracing::{debug, warn};
 
 #[derive(PartialEq, Copy, Clone, Hash, Eq, Debug, Serialize, Deserialize)]
 pub enum Mode {
     Channel,
     RemoteControl,
+    SendToChannel,
 }
 
+/// How the user's input is matched against the channel's entries.
 #[derive(PartialEq, Copy, Clone, Hash, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum MatchingMode {
-    Substring,
     Fuzzy,
+    /// Each word must appear as is (fuzzy matching with `'` prepended).
+    Substring,
+    /// Each word must appear at the start of the entry.
+    Prefix,
+    /// Fuzzy matching that always respects case and never normalizes
+    /// unicode characters.
+    CaseSensitive,
+    /// The input is a regular expression that entries must match.
+    Regex,
+}
+
+impl MatchingMode {
+    /// The mode that comes after this one when cycling through modes.
+    #[must_use]
+    pub fn cycle(self) -> Self {
+        match self {
+            MatchingMode::Fuzzy => MatchingMode::Substring,
+            MatchingMode::Substring => MatchingMode::Prefix,
+            MatchingMode::Prefix => MatchingMode::CaseSensitive,
+            MatchingMode::CaseSensitive => MatchingMode::Regex,
+            MatchingMode::Regex => MatchingMode::Fuzzy,
+        }
+    }
+}
+
+impl Display for MatchingMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchingMode::Fuzzy => write!(f, "fuzzy"),
+            MatchingMode::Substring => write!(f, "substring"),
+            MatchingMode::Prefix => write!(f, "prefix"),
+            MatchingMode::CaseSensitive => write!(f, "case-sensitive"),
+            MatchingMode::Regex => write!(f, "regex"),
+        }
+    }
+}
+
+type PreviewHandles = (
+    UnboundedSender<PreviewRequest>,
+    UnboundedReceiver<PreviewUpdate>,
+);
+
+/// The maximum number of channels kept around to go back to.
+const MAX_CHANNEL_HISTORY: usize = 16;
+
+/// A channel that was put in the background along with its previewer and UI
+/// state, so that it can be brought back as it was (see `Television::go_back`
+/// and `Television::go_forward`).
+struct ChannelSnapshot {
+    channel: CableChannel,
+    preview_handles: Option<PreviewHandles>,
+    preview_processes: ProcessTracker,
+    preview_state: PreviewState,
+    results_picker: Picker,
+    current_pattern: String,
+    currently_selected: Option<Entry>,
+    matching_mode: MatchingMode,
+    invalid_pattern: bool,
+}
+
+impl ChannelSnapshot {
+    /// Stop any work the channel and its previewer are still doing while
+    /// keeping their state around.
+    fn pause(&mut self) {
+        self.channel.pause();
+        self.preview_processes.kill_all();
+    }
+
+    /// Stop the channel and previewer for good.
+    fn shutdown(&self) {
+        self.channel.shutdown();
+        self.preview_processes.shutdown();
+        if let Some((sender, _)) = &self.preview_handles {
+            let _ = sender.send(PreviewRequest::Shutdown);
+        }
+    }
+}
+
+impl Drop for ChannelSnapshot {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
 }
 
 pub struct Television {
@@ -54,11 +149,16 @@ pub struct Television {
     pub currently_selected: Option<Entry>,
     pub current_pattern: String,
     pub matching_mode: MatchingMode,
+    /// The matching mode channels use unless configured otherwise.
+    default_matching_mode: MatchingMode,
+    /// Whether the current pattern couldn't be used for matching (e.g. an
+    /// invalid regex), in which case the previous results are kept.
+    invalid_pattern: bool,
     pub results_picker: Picker,
     pub rc_picker: Picker,
     pub preview_state: PreviewState,
-    pub preview_handles:
-        Option<(UnboundedSender<PreviewRequest>, UnboundedReceiver<Preview>)>,
+    pub preview_handles: Option<PreviewHandles>,
+    preview_processes: ProcessTracker,
     pub spinner: Spinner,
     pub spinner_state: SpinnerState,
     pub app_metadata: AppMetadata,
@@ -66,6 +166,25 @@ pub struct Television {
     pub ticks: u64,
     pub ui_state: UiState,
     pub no_help: bool,
+    /// The entry to move the cursor back to once the channel's source is
+    /// done reloading.
+    entry_to_restore: Option<Entry>,
+    /// The entries to send to the channel picked in send to channel mode.
+    entries_to_send: Vec<Entry>,
+    /// The channels to go back to, the most recent one last.
+    back_history: Vec<ChannelSnapshot>,
+    /// The channels to go forward to after having gone back, the most recent
+    /// one last.
+    forward_history: Vec<ChannelSnapshot>,
+    /// The queries previously submitted in the current channel.
+    query_history: QueryHistory,
+    /// The messages currently shown to the user (errors, warnings, etc.).
+    pub notifications: Notifications,
+    /// The last source failure of the current channel the user was notified
+    /// of, so that they're only notified once per failure.
+    notified_source_error: Option<SourceError>,
+    /// The size of the preview window the current previewer was told about.
+    preview_size: Option<PreviewSize>,
 }
 
 impl Television {
@@ -87,9 +206,19 @@ impl Television {
         }
 
         // previewer
-        let preview_handles = Self::setup_previewer(channel_prototype);
+        let preview_processes = ProcessTracker::default();
+        let preview_handles = Self::setup_previewer(
+            &config,
+            channel_prototype,
+            &preview_processes,
+        );
 
-        let mut channel = CableChannel::new(channel_prototype);
+        let channel = Self::new_channel(&config, channel_prototype, &[]);
+        let query_history = QueryHistory::load(
+            &config.application.data_dir,
+            &channel.name,
+            config.application.history_size,
+        );
 
         let app_metadata = AppMetadata::new(
             env!("CARGO_PKG_VERSION").to_string(),
@@ -100,7 +229,6 @@ impl Television {
         );
         let colorscheme = (&Theme::from_name(&config.ui.theme)).into();
 
-        channel.find(&input.unwrap_or(EMPTY_STRING.to_string()));
         let spinner = Spinner::default();
 
         let preview_state = PreviewState::new(
@@ -121,13 +249,16 @@ impl Television {
             config.ui.no_help = true;
         }
 
-        let matching_mode = if exact {
+        let default_matching_mode = if exact {
             MatchingMode::Substring
         } else {
             MatchingMode::Fuzzy
         };
+        let matching_mode = channel_prototype
+            .matching_mode
+            .unwrap_or(default_matching_mode);
 
-        Self {
+        let mut television = Self {
             action_tx,
             config,
             channel,
@@ -137,9 +268,12 @@ impl Television {
             current_pattern: EMPTY_STRING.to_string(),
             results_picker,
             matching_mode,
+            default_matching_mode,
+            invalid_pattern: false,
             rc_picker: Picker::default(),
             preview_state,
             preview_handles,
+            preview_processes,
             spinner,
             spinner_state: SpinnerState::from(&spinner),
             app_metadata,
@@ -147,21 +281,33 @@ impl Television {
             ticks: 0,
             ui_state: UiState::default(),
             no_help,
-        }
+            entry_to_restore: None,
+            entries_to_send: Vec::new(),
+            back_history: Vec::new(),
+            forward_history: Vec::new(),
+            query_history,
+            notifications: Notifications::default(),
+            notified_source_error: None,
+            preview_size: None,
+        };
+        television.find(&input.unwrap_or(EMPTY_STRING.to_string()));
+        television
     }
 
     fn setup_previewer(
+        config: &Config,
         channel_prototype: &ChannelPrototype,
-    ) -> Option<(UnboundedSender<PreviewRequest>, UnboundedReceiver<Preview>)>
-    {
+        processes: &ProcessTracker,
+    ) -> Option<PreviewHandles> {
         if channel_prototype.preview_command.is_some() {
             let (pv_request_tx, pv_request_rx) = unbounded_channel();
             let (pv_preview_tx, pv_preview_rx) = unbounded_channel();
             let previewer = Previewer::new(
                 channel_prototype.preview_command.clone().unwrap(),
-                PreviewerConfig::default(),
+                channel_prototype.previewer_config(config.previewer.clone()),
                 pv_request_rx,
                 pv_preview_tx,
+                processes.clone(),
             );
             tokio::spawn(async move { previewer.run().await });
             Some((pv_request_tx, pv_preview_rx))
@@ -172,6 +318,27 @@ impl Television {
 
     pub fn update_ui_state(&mut self, ui_state: UiState) {
         self.ui_state = ui_state;
+        self.resize_preview();
+    }
+
+    /// Let the previewer know about the size of the preview window if it
+    /// changed, and preview the entry under the cursor again with it.
+    fn resize_preview(&mut self) {
+        let Some(size) = self.ui_state.layout.preview_window.map(preview_size)
+        else {
+            return;
+        };
+        if self.preview_size == Some(size) {
+            return;
+        }
+        self.preview_size = Some(size);
+        if let Some((sender, _)) = &self.preview_handles {
+            let _ = sender.send(PreviewRequest::Resize(size));
+            if let Some(entry) = &self.currently_selected {
+                let _ = sender
+                    .send(PreviewRequest::Preview(Ticket::new(entry.clone())));
+            }
+        }
     }
 
     pub fn dump_context(&self) -> Ctx {
@@ -180,7 +347,16 @@ impl Television {
             self.channel.selected_entries().clone(),
             self.channel.total_count(),
             self.channel.running(),
-        );
+        )
+        .with_actions(
+            self.channel
+                .actions()
+                .iter()
+                .map(|(name, action)| (name.clone(), action.key.to_string()))
+                .collect(),
+        )
+        .with_matching_mode(self.matching_mode, self.invalid_pattern)
+        .with_source_error(self.channel.source_error());
         let tv_state = TvState::new(
             self.mode,
             self.currently_selected.clone(),
@@ -189,7 +365,8 @@ impl Television {
             channel_state,
             self.spinner,
             self.preview_state.for_render_context(),
-        );
+        )
+        .with_notifications(self.notifications.clone());
 
         Ctx::new(
             tv_state,
@@ -201,37 +378,237 @@ impl Television {
         )
     }
 
+    /// The custom action of the current channel bound to the given key, if
+    /// any.
+    pub fn custom_action_for_key(&self, key: &Key) -> Option<Action> {
+        if self.mode != Mode::Channel {
+            return None;
+        }
+        self.channel
+            .action_for_key(key)
+            .map(|name| Action::CustomAction(name.to_string()))
+    }
+
+    /// Format the command of the current channel's custom action with the
+    /// given name against the entry under the cursor and the selected
+    /// entries.
+    pub fn format_custom_action(
+        &self,
+        name: &str,
+    ) -> Option<(ExecutionMode, String)> {
+        let action = self.channel.actions().get(name)?;
+        let current = self.get_selected_entry(Some(Mode::Channel))?;
+        let mut selected = self
+            .channel
+            .selected_entries()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        selected.sort_by(|a, b| a.name.cmp(&b.name));
+        Some((action.mode, action.format_command(&current, &selected)))
+    }
+
     pub fn current_channel(&self) -> String {
         self.channel.name.clone()
     }
 
+    /// Create a channel for the given prototype, fed with the given entries
+    /// and ranking its entries by frecency if configured to.
+    fn new_channel(
+        config: &Config,
+        channel_prototype: &ChannelPrototype,
+        input: &[Entry],
+    ) -> CableChannel {
+        let channel = CableChannel::with_matcher_config(
+            channel_prototype,
+            input,
+            config.matcher.clone(),
+        )
+        .with_query_debounce(channel_prototype.query_debounce(
+            Duration::from_millis(config.application.query_debounce),
+        ));
+        if channel_prototype.frecency.is_some() {
+            channel.with_frecency(Frecency::load(
+                &config.application.data_dir,
+                &channel_prototype.name,
+            ))
+        } else {
+            channel
+        }
+    }
+
     pub fn change_channel(&mut self, channel_prototype: &ChannelPrototype) {
-        self.preview_state.reset();
-        self.preview_state.enabled =
-            channel_prototype.preview_command.is_some();
-        self.reset_picker_selection();
-        self.reset_picker_input();
-        self.current_pattern = EMPTY_STRING.to_string();
+        self.change_channel_with_input(channel_prototype, &[]);
+    }
+
+    /// Change to the given channel, feeding its source command with the given
+    /// entries (see `CableChannel::with_input`).
+    pub fn change_channel_with_input(
+        &mut self,
+        channel_prototype: &ChannelPrototype,
+        input: &[Entry],
+    ) {
+        let preview_processes = ProcessTracker::default();
+        let mut results_picker = self.results_picker.clone();
+        results_picker.reset_selection();
+        results_picker.reset_input();
+        let snapshot = ChannelSnapshot {
+            channel: Self::new_channel(&self.config, channel_prototype, input),
+            preview_handles: Self::setup_previewer(
+                &self.config,
+                channel_prototype,
+                &preview_processes,
+            ),
+            preview_processes,
+            preview_state: PreviewState::new(
+                channel_prototype.preview_command.is_some(),
+                Preview::default(),
+                0,
+                None,
+            ),
+            results_picker,
+            current_pattern: EMPTY_STRING.to_string(),
+            currently_selected: None,
+            matching_mode: channel_prototype
+                .matching_mode
+                .unwrap_or(self.default_matching_mode),
+            invalid_pattern: false,
+        };
+        let previous = self.switch_to(snapshot);
+        self.back_history.push(previous);
+        if self.back_history.len() > MAX_CHANNEL_HISTORY {
+            self.back_history.remove(0);
+        }
+        self.forward_history.clear();
+        debug!("Changed channel to {:?}", channel_prototype);
+    }
+
+    /// Go back to the channel that was active before the last channel
+    /// change, as it was left.
+    pub fn go_back(&mut self) {
+        if let Some(snapshot) = self.back_history.pop() {
+            let current = self.switch_to(snapshot);
+            self.forward_history.push(current);
+            debug!("Went back to channel {:?}", self.channel.name);
+        }
+    }
+
+    /// Go forward to the channel that was left by going back.
+    pub fn go_forward(&mut self) {
+        if let Some(snapshot) = self.forward_history.pop() {
+            let current = self.switch_to(snapshot);
+            self.back_history.push(current);
+            debug!("Went forward to channel {:?}", self.channel.name);
+        }
+    }
+
+    /// Make the given channel the current one and return the (paused)
+    /// channel that was current so far.
+    fn switch_to(&mut self, mut snapshot: ChannelSnapshot) -> ChannelSnapshot {
+        std::mem::swap(&mut self.channel, &mut snapshot.channel);
+        std::mem::swap(
+            &mut self.preview_handles,
+            &mut snapshot.preview_handles,
+        );
+        // the previewer might not know about the current size yet
+        self.preview_size = None;
+        std::mem::swap(
+            &mut self.preview_processes,
+            &mut snapshot.preview_processes,
+        );
+        std::mem::swap(&mut self.preview_state, &mut snapshot.preview_state);
+        std::mem::swap(&mut self.results_picker, &mut snapshot.results_picker);
+        std::mem::swap(
+            &mut self.current_pattern,
+            &mut snapshot.current_pattern,
+        );
+        std::mem::swap(
+            &mut self.currently_selected,
+            &mut snapshot.currently_selected,
+        );
+        std::mem::swap(&mut self.matching_mode, &mut snapshot.matching_mode);
+        std::mem::swap(
+            &mut self.invalid_pattern,
+            &mut snapshot.invalid_pattern,
+        );
+        snapshot.pause();
+        self.channel.resume();
+        self.entry_to_restore = None;
+        self.query_history = QueryHistory::load(
+            &self.config.application.data_dir,
+            &self.channel.name,
+            self.config.application.history_size,
+        );
+        snapshot
+    }
+
+    /// Re-run the current channel's source while keeping the current pattern
+    /// and, if it's still there once reloaded, the entry under the cursor.
+    pub fn reload_source(&mut self) {
+        self.channel.reload();
+        if self.entry_to_restore.is_none() {
+            self.entry_to_restore.clone_from(&self.currently_selected);
+        }
+        // the previews of the reloaded entries might have changed as well
+        self.invalidate_previews();
+    }
+
+    /// Forget the cached previews and preview the entry under the cursor
+    /// again.
+    pub fn invalidate_previews(&mut self) {
+        if let Some((sender, _)) = &self.preview_handles {
+            let _ = sender.send(PreviewRequest::InvalidateCache);
+            if let Some(entry) = &self.currently_selected {
+                let _ = sender
+                    .send(PreviewRequest::Preview(Ticket::new(entry.clone())));
+            }
+        }
+    }
+
+    /// Stop the current channel and previewer as well as the ones in the
+    /// channel history, killing any process they might still be running.
+    pub fn shutdown(&self) {
+        for snapshot in self.back_history.iter().chain(&self.forward_history) {
+            snapshot.shutdown();
+        }
         self.channel.shutdown();
+        self.preview_processes.shutdown();
         if let Some((sender, _)) = &self.preview_handles {
-            sender
-                .send(PreviewRequest::Shutdown)
-                .expect("Failed to send shutdown signal to previewer");
+            // the previewer might already have exited on its own
+            let _ = sender.send(PreviewRequest::Shutdown);
         }
-        self.preview_handles = Self::setup_previewer(channel_prototype);
-        self.channel = CableChannel::new(channel_prototype);
-        debug!("Changed channel to {:?}", channel_prototype);
     }
 
     pub fn find(&mut self, pattern: &str) {
         match self.mode {
             Mode::Channel => {
-                self.channel.find(
-                    Self::preprocess_pattern(self.matching_mode, pattern)
-                        .as_str(),
-                );
+                let pattern = if self.channel.is_dynamic() {
+                    let (query, filter) = split_dynamic_query(pattern);
+                    self.channel.set_query(query);
+                    filter
+                } else {
+                    pattern
+                };
+                self.invalid_pattern = false;
+                match self.matching_mode {
+                    // an empty regex would match everything anyway
+                    MatchingMode::Regex if !pattern.is_empty() => {
+                        if let Err(e) = self.channel.find_regex(pattern) {
+                            debug!("Invalid regex {:?}: {}", pattern, e);
+                            self.invalid_pattern = true;
+                        }
+                    }
+                    mode => {
+                        self.channel.set_case_sensitive(
+                            mode == MatchingMode::CaseSensitive,
+                        );
+                        self.channel.find(
+                            Self::preprocess_pattern(mode, pattern).as_str(),
+                        );
+                    }
+                }
             }
-            Mode::RemoteControl => {
+            Mode::RemoteControl | Mode::SendToChannel => {
                 if let Some(rc) = self.remote_control.as_mut() {
                     rc.find(pattern);
                 }
@@ -240,18 +617,31 @@ impl Television {
     }
 
     fn preprocess_pattern(mode: MatchingMode, pattern: &str) -> String {
-        if mode == MatchingMode::Substring {
-            return pattern
-                .split_ascii_whitespace()
-                .map(|x| {
-                    let mut new = x.to_string();
-                    new.insert(0, '\'');
-                    new
-                })
-                .collect::<Vec<String>>()
-                .join(" ");
-        }
-        pattern.to_string()
+        let prefix = match mode {
+            MatchingMode::Substring => '\'',
+            MatchingMode::Prefix => '^',
+            _ => return pattern.to_string(),
+        };
+        pattern
+            .split_ascii_whitespace()
+            .map(|x| {
+                let mut new = x.to_string();
+                new.insert(0, prefix);
+                new
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Switch to the next matching mode and match the current pattern again
+    /// with it.
+    pub fn cycle_matching_mode(&mut self) {
+        self.matching_mode = self.matching_mode.cycle();
+        debug!("Switched to {} matching", self.matching_mode);
+        let pattern = self.current_pattern.clone();
+        self.find(&pattern);
+        self.reset_picker_selection();
+        self.entry_to_restore = None;
     }
 
     #[must_use]
@@ -263,7 +653,7 @@ impl Television {
                 }
                 None
             }
-            Mode::RemoteControl => {
+            Mode::RemoteControl | Mode::SendToChannel => {
                 if let Some(i) = self.rc_picker.selected() {
                     if let Some(rc) = &self.remote_control {
                         return rc.get_result(i.try_into().unwrap());
@@ -280,7 +670,7 @@ impl Television {
         mode: Option<Mode>,
     ) -> Option<FxHashSet<Entry>> {
         if self.channel.selected_entries().is_empty()
-            || matches!(mode, Some(Mode::RemoteControl))
+            || matches!(mode, Some(Mode::RemoteControl | Mode::SendToChannel))
         {
             return self.get_selected_entry(mode).map(|e| {
                 let mut set = HashSet::with_hasher(FxBuildHasher);
@@ -296,7 +686,7 @@ impl Television {
             Mode::Channel => {
                 (self.channel.result_count(), &mut self.results_picker)
             }
-            Mode::RemoteControl => (
+            Mode::RemoteControl | Mode::SendToChannel => (
                 self.remote_control.as_ref().unwrap().total_count(),
                 &mut self.rc_picker,
             ),
@@ -304,6 +694,7 @@ impl Television {
         if result_count == 0 {
             return;
         }
+        self.entry_to_restore = None;
         picker.select_prev(
             step,
             result_count as usize,
@@ -316,7 +707,7 @@ impl Television {
             Mode::Channel => {
                 (self.channel.result_count(), &mut self.results_picker)
             }
-            Mode::RemoteControl => (
+            Mode::RemoteControl | Mode::SendToChannel => (
                 self.remote_control.as_ref().unwrap().total_count(),
                 &mut self.rc_picker,
             ),
@@ -324,6 +715,7 @@ impl Television {
         if result_count == 0 {
             return;
         }
+        self.entry_to_restore = None;
         picker.select_next(
             step,
             result_count as usize,
@@ -334,7 +726,7 @@ impl Television {
     fn reset_picker_selection(&mut self) {
         match self.mode {
             Mode::Channel => self.results_picker.reset_selection(),
-            Mode::RemoteControl => {
+            Mode::RemoteControl | Mode::SendToChannel => {
                 self.rc_picker.reset_selection();
             }
         }
@@ -343,13 +735,28 @@ impl Television {
     fn reset_picker_input(&mut self) {
         match self.mode {
             Mode::Channel => self.results_picker.reset_input(),
-            Mode::RemoteControl => {
+            Mode::RemoteControl | Mode::SendToChannel => {
                 self.rc_picker.reset_input();
             }
         }
     }
 }
 
+/// Separates the query passed to a dynamic source command from the pattern
+/// used to filter its results.
+const DYNAMIC_QUERY_SEPARATOR: &str = " // ";
+
+/// Split the user's input into the query to run a dynamic source command
+/// with and the pattern to filter its results with.
+///
+/// E.g. `foo // bar` runs the source with `foo` and then fuzzy matches `bar`
+/// against its results.
+fn split_dynamic_query(input: &str) -> (&str, &str) {
+    input
+        .split_once(DYNAMIC_QUERY_SEPARATOR)
+        .unwrap_or((input, EMPTY_STRING))
+}
+
 /// Always render the first N ticks.
 ///
 /// This is to ensure there are no startup artefacts and the UI
@@ -364,6 +771,9 @@ const RENDERING_INTERVAL: u64 = 10;
 /// This ensures that the UI stays in sync with the channel
 /// state (displaying a spinner, updating results, etc.).
 const RENDERING_INTERVAL_FAST: u64 = 3;
+/// How many results to look through when trying to restore the entry that
+/// was under the cursor before reloading a channel.
+const MAX_RESTORE_SEARCH_DEPTH: u32 = 10_000;
 
 impl Television {
     fn should_render(&self, action: &Action) -> bool {
@@ -397,6 +807,17 @@ impl Television {
                     | Action::ToggleHelp
                     | Action::TogglePreview
                     | Action::CopyEntryToClipboard
+                    | Action::ReloadSource
+                    | Action::InvalidatePreviewCache
+                    | Action::GoBack
+                    | Action::GoForward
+                    | Action::PreviousQuery
+                    | Action::NextQuery
+                    | Action::ForgetEntry
+                    | Action::CycleMatchingMode
+                    | Action::Error(_)
+                    | Action::Warning(_)
+                    | Action::DismissNotification
             )
     }
 
@@ -408,16 +829,37 @@ impl Television {
             self.preview_state.reset();
             return Ok(());
         }
+        let neighbours = if *selected_entry == self.currently_selected {
+            Vec::new()
+        } else {
+            self.neighbouring_entries()
+        };
         if let Some((sender, receiver)) = &mut self.preview_handles {
             // preview requests
             if *selected_entry != self.currently_selected {
                 sender.send(PreviewRequest::Preview(Ticket::new(
                     selected_entry.as_ref().unwrap().clone(),
                 )))?;
+                if !neighbours.is_empty() {
+                    sender.send(PreviewRequest::Prefetch(neighbours))?;
+                }
             }
             // available previews
             let entry = selected_entry.as_ref().unwrap();
-            if let Ok(preview) = receiver.try_recv() {
+            let mut updated = false;
+            while let Ok(update) = receiver.try_recv() {
+                updated = true;
+                let preview = match update {
+                    PreviewUpdate::Preview(preview) => preview,
+                    PreviewUpdate::Append(chunk) => {
+                        self.preview_state.append(&chunk);
+                        continue;
+                    }
+                    PreviewUpdate::TimedOut => {
+                        self.preview_state.time_out();
+                        continue;
+                    }
+                };
                 let scroll = entry
                     .line_number
                     .unwrap_or(0)
@@ -435,18 +877,59 @@ impl Television {
                     // if the scroll doesn't fit in a u16, just scroll to the top
                     // this is a current limitation of ratatui
                     .unwrap_or(0);
+                if let Some(error) = &preview.error {
+                    self.notifications.push(Notification::error(error));
+                }
                 self.preview_state.update(
                     preview,
                     scroll,
                     entry.line_number.and_then(|l| l.try_into().ok()),
                 );
+            }
+            if updated {
                 self.action_tx.send(Action::Render)?;
             }
         }
         Ok(())
     }
 
+    /// The entries around the selected one whose previews should be
+    /// prefetched, nearest first and alternating between the next and the
+    /// previous ones.
+    fn neighbouring_entries(&self) -> Vec<Entry> {
+        let Some(selected) = self
+            .results_picker
+            .selected()
+            .and_then(|i| u32::try_from(i).ok())
+        else {
+            return Vec::new();
+        };
+        let count = self.channel.result_count();
+        let mut entries = Vec::new();
+        for distance in 1..=self.config.previewer.prefetch {
+            let Ok(distance) = u32::try_from(distance) else {
+                break;
+            };
+            let next = selected.checked_add(distance).filter(|&i| i < count);
+            let previous = selected.checked_sub(distance);
+            if next.is_none() && previous.is_none() {
+                break;
+            }
+            entries.extend(
+                [next, previous]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|i| self.channel.get_result(i)),
+            );
+        }
+        entries
+    }
+
     pub fn update_results_picker_state(&mut self) {
+        // only look at the channel's source *before* ticking the matcher so
+        // that the results account for everything it produced
+        let reloaded =
+            self.entry_to_restore.is_some() && !self.channel.running();
         if self.results_picker.selected().is_none()
             && self.channel.result_count() > 0
         {
@@ -459,6 +942,48 @@ impl Television {
             u32::try_from(self.results_picker.offset()).unwrap(),
         );
         self.results_picker.total_items = self.channel.result_count();
+
+        if reloaded && !self.channel.running() {
+            self.restore_selected_entry();
+            self.results_picker.entries = self.channel.results(
+                self.ui_state.layout.results.height.into(),
+                u32::try_from(self.results_picker.offset()).unwrap(),
+            );
+        }
+    }
+
+    /// Move the cursor back to `entry_to_restore` if it can be found among
+    /// the first results, or back to the top if the cursor ended up out of
+    /// bounds.
+    fn restore_selected_entry(&mut self) {
+        let Some(entry) = self.entry_to_restore.take() else {
+            return;
+        };
+        let result_count = self.channel.result_count();
+        let found = (0..result_count.min(MAX_RESTORE_SEARCH_DEPTH))
+            .find(|i| self.channel.get_result(*i).as_ref() == Some(&entry));
+        match found {
+            Some(index) => {
+                let index = index as usize;
+                let relative = self
+                    .results_picker
+                    .relative_state
+                    .selected()
+                    .unwrap_or(0)
+                    .min(index);
+                self.results_picker.select(Some(index));
+                self.results_picker.relative_select(Some(relative));
+            }
+            None => {
+                if self
+                    .results_picker
+                    .selected()
+                    .is_some_and(|i| i >= result_count as usize)
+                {
+                    self.results_picker.reset_selection();
+                }
+            }
+        }
     }
 
     pub fn update_rc_picker_state(&mut self) {
@@ -482,7 +1007,9 @@ impl Television {
     pub fn handle_input_action(&mut self, action: &Action) {
         let input = match self.mode {
             Mode::Channel => &mut self.results_picker.input,
-            Mode::RemoteControl => &mut self.rc_picker.input,
+            Mode::RemoteControl | Mode::SendToChannel => {
+                &mut self.rc_picker.input
+            }
         };
         input.handle(convert_action_to_input_request(action).unwrap());
         match action {
@@ -493,15 +1020,49 @@ impl Television {
             | Action::DeleteNextChar => {
                 let new_pattern = input.value().to_string();
                 if new_pattern != self.current_pattern {
+                    if self.mode == Mode::Channel {
+                        self.query_history.reset();
+                    }
                     self.current_pattern.clone_from(&new_pattern);
                     self.find(&new_pattern);
                     self.reset_picker_selection();
+                    self.entry_to_restore = None;
                 }
             }
             _ => {}
         }
     }
 
+    /// Replace the current channel's input with the previous query from its
+    /// history.
+    pub fn previous_query(&mut self) {
+        if let Some(query) = self
+            .query_history
+            .previous_query(&self.current_pattern)
+            .map(str::to_string)
+        {
+            self.set_input(query);
+        }
+    }
+
+    /// Replace the current channel's input with the next query from its
+    /// history.
+    pub fn next_query(&mut self) {
+        if let Some(query) =
+            self.query_history.next_query().map(str::to_string)
+        {
+            self.set_input(query);
+        }
+    }
+
+    fn set_input(&mut self, value: String) {
+        self.results_picker.input = Input::new(value.clone());
+        self.find(&value);
+        self.current_pattern = value;
+        self.reset_picker_selection();
+        self.entry_to_restore = None;
+    }
+
     pub fn handle_toggle_rc(&mut self) {
         if self.remote_control.is_none() {
             return;
@@ -510,16 +1071,45 @@ impl Television {
             Mode::Channel => {
                 self.mode = Mode::RemoteControl;
             }
-            Mode::RemoteControl => {
-                // this resets the RC picker
-                self.reset_picker_input();
-                self.remote_control.as_mut().unwrap().find(EMPTY_STRING);
-                self.reset_picker_selection();
-                self.mode = Mode::Channel;
+            Mode::RemoteControl | Mode::SendToChannel => {
+                self.leave_remote_control();
             }
         }
     }
 
+    /// Open the remote control to pick a channel to send the selected
+    /// entries (or the entry under the cursor) to.
+    pub fn handle_toggle_send_to_channel(&mut self) {
+        if self.remote_control.is_none() {
+            return;
+        }
+        match self.mode {
+            Mode::Channel => {
+                let Some(entries) =
+                    self.get_selected_entries(Some(Mode::Channel))
+                else {
+                    return;
+                };
+                let mut entries = entries.into_iter().collect::<Vec<_>>();
+                entries.sort_by(|a, b| a.name.cmp(&b.name));
+                self.entries_to_send = entries;
+                self.mode = Mode::SendToChannel;
+            }
+            Mode::RemoteControl | Mode::SendToChannel => {
+                self.leave_remote_control();
+            }
+        }
+    }
+
+    fn leave_remote_control(&mut self) {
+        // this resets the RC picker
+        self.reset_picker_input();
+        self.remote_control.as_mut().unwrap().find(EMPTY_STRING);
+        self.reset_picker_selection();
+        self.entries_to_send.clear();
+        self.mode = Mode::Channel;
+    }
+
     pub fn handle_toggle_selection(&mut self, action: &Action) {
         if matches!(self.mode, Mode::Channel) {
             if let Some(entry) = &self.currently_selected {
@@ -536,27 +1126,41 @@ impl Television {
     pub fn handle_confirm_selection(&mut self) -> Result<()> {
         match self.mode {
             Mode::Channel => {
+                if let Err(e) = self.query_history.add(&self.current_pattern) {
+                    warn!("Failed to save query history: {}", e);
+                }
+                if let Some(entries) = self.get_selected_entries(None) {
+                    if let Err(e) = self.channel.record_selection(&entries) {
+                        warn!("Failed to save frecency records: {}", e);
+                    }
+                }
                 self.action_tx.send(Action::SelectAndExit)?;
             }
-            Mode::RemoteControl => {
+            Mode::RemoteControl | Mode::SendToChannel => {
                 if let Some(entry) = self.get_selected_entry(None) {
                     let new_channel = self
                         .remote_control
                         .as_ref()
                         .unwrap()
                         .zap(entry.name.as_str())?;
-                    // this resets the RC picker
-                    self.reset_picker_selection();
-                    self.reset_picker_input();
-                    self.remote_control.as_mut().unwrap().find(EMPTY_STRING);
-                    self.mode = Mode::Channel;
-                    self.change_channel(&new_channel);
+                    let input = std::mem::take(&mut self.entries_to_send);
+                    self.leave_remote_control();
+                    self.change_channel_with_input(&new_channel, &input);
                 }
             }
         }
         Ok(())
     }
 
+    /// Forget the frecency of the currently selected entry.
+    pub fn handle_forget_entry(&mut self) {
+        if let Some(entry) = self.get_selected_entry(Some(Mode::Channel)) {
+            if let Err(e) = self.channel.forget(&entry) {
+                warn!("Failed to save frecency records: {}", e);
+            }
+        }
+    }
+
     pub fn handle_copy_entry_to_clipboard(&mut self) {
         if self.mode == Mode::Channel {
             if let Some(entries) = self.get_selected_entries(None) {
@@ -566,7 +1170,15 @@ impl Television {
                     .collect::<Vec<_>>()
                     .join(" ");
 
-                tokio::spawn(CLIPBOARD.set(copied_string));
+                let action_tx = self.action_tx.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = CLIPBOARD.set(copied_string).await {
+                        warn!("Failed to copy to the clipboard: {}", e);
+                        let _ = action_tx.send(Action::Error(format!(
+                            "Failed to copy to the clipboard: {e}"
+                        )));
+                    }
+                });
             }
         }
     }
@@ -622,6 +1234,39 @@ impl Television {
             Action::ToggleRemoteControl => {
                 self.handle_toggle_rc();
             }
+            Action::ToggleSendToChannel => {
+                self.handle_toggle_send_to_channel();
+            }
+            Action::GoBack => {
+                if self.mode == Mode::Channel {
+                    self.go_back();
+                }
+            }
+            Action::GoForward => {
+                if self.mode == Mode::Channel {
+                    self.go_forward();
+                }
+            }
+            Action::PreviousQuery => {
+                if self.mode == Mode::Channel {
+                    self.previous_query();
+                }
+            }
+            Action::NextQuery => {
+                if self.mode == Mode::Channel {
+                    self.next_query();
+                }
+            }
+            Action::CycleMatchingMode => {
+                if self.mode == Mode::Channel {
+                    self.cycle_matching_mode();
+                }
+            }
+            Action::ForgetEntry => {
+                if self.mode == Mode::Channel {
+                    self.handle_forget_entry();
+                }
+            }
             Action::ToggleSelectionDown | Action::ToggleSelectionUp => {
                 self.handle_toggle_selection(action);
             }
@@ -641,11 +1286,76 @@ impl Television {
                 self.config.ui.show_preview_panel =
                     !self.config.ui.show_preview_panel;
             }
+            Action::ReloadSource => {
+                self.reload_source();
+            }
+            Action::InvalidatePreviewCache => {
+                if self.mode == Mode::Channel {
+                    self.invalidate_previews();
+                }
+            }
+            Action::Error(message) => {
+                self.notifications.push(Notification::error(message));
+            }
+            Action::Warning(message) => {
+                self.notifications.push(Notification::warning(message));
+            }
+            Action::DismissNotification => {
+                self.notifications.dismiss();
+            }
+            Action::Tick => {
+                if self.channel.should_run_query() {
+                    self.channel.reload();
+                } else if self.channel.should_reload() {
+                    self.reload_source();
+                }
+                let expired = self.notifications.prune(Instant::now());
+                if expired || self.notify_source_error() {
+                    self.action_tx.send(Action::Render)?;
+                }
+            }
             _ => {}
         }
         Ok(())
     }
 
+    /// Notify the user of the current channel's source command failing,
+    /// returning whether they had not been notified of that failure yet.
+    ///
+    /// The failure itself is also reported in the results pane (see
+    /// `SourceError`).
+    fn notify_source_error(&mut self) -> bool {
+        let source_error = self.channel.source_error();
+        if source_error == self.notified_source_error {
+            return false;
+        }
+        self.notified_source_error.clone_from(&source_error);
+        let Some(error) = source_error else {
+            return false;
+        };
+        let mut message = format!("{}: {error}", self.channel.name);
+        if let Some(line) =
+            error.stderr().lines().rfind(|l| !l.trim().is_empty())
+        {
+            message.push('\n');
+            message.push_str(line.trim());
+        }
+        let notification = match error {
+            SourceError::Spawn(_) => Notification::error(message),
+            SourceError::Failed { stderr, .. } if !stderr.is_empty() => {
+                Notification::error(message)
+            }
+            // e.g. `grep` and `rg` exit with 1 when nothing matches
+            SourceError::Failed { .. } => Notification::warning(message),
+            SourceError::Empty { stderr } if !stderr.is_empty() => {
+                Notification::warning(message)
+            }
+            SourceError::Empty { .. } => return false,
+        };
+        self.notifications.push(notification);
+        true
+    }
+
     #[allow(clippy::unused_async)]
     /// Update the television state based on the action provided.
     ///
@@ -680,7 +1390,15 @@ impl Television {
 
 #[cfg(test)]
 mod test {
-    use crate::television::{MatchingMode, Television};
+    use crate::television::{split_dynamic_query, MatchingMode, Television};
+
+    #[test]
+    fn test_split_dynamic_query() {
+        assert_eq!(split_dynamic_query("foo bar"), ("foo bar", ""));
+        assert_eq!(split_dynamic_query("foo // bar"), ("foo", "bar"));
+        assert_eq!(split_dynamic_query("a // b // c"), ("a", "b // c"));
+        assert_eq!(split_dynamic_query(""), ("", ""));
+    }
 
     #[test]
     fn test_prompt_preprocessing() {
@@ -696,5 +1414,28 @@ mod test {
             Television::preprocess_pattern(MatchingMode::Substring, mult_word),
             expect_mult
         );
+        assert_eq!(
+            Television::preprocess_pattern(MatchingMode::Prefix, mult_word),
+            "^this ^is ^a ^specific ^test"
+        );
+        assert_eq!(
+            Television::preprocess_pattern(MatchingMode::Regex, mult_word),
+            mult_word
+        );
+    }
+
+    #[test]
+    fn test_cycle_matching_mode() {
+        let mut mode = MatchingMode::Fuzzy;
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            seen.push(mode.to_string());
+            mode = mode.cycle();
+        }
+        assert_eq!(mode, MatchingMode::Fuzzy);
+        assert_eq!(
+            seen,
+            vec!["fuzzy", "substring", "prefix", "case-sensitive", "regex"]
+        );
     }
 }
@@ -0,0 +1,95 @@
+use ansi_to_tui::IntoText;
+use ratatui::style::{Color, Style};
+
+/// A range of characters, as `(start, end)` character indices, along with
+/// its style.
+pub type StyledRange = (u32, u32, Style);
+
+/// Parse the ANSI escape sequences of the given text.
+///
+/// Returns the text stripped of its escape sequences along with the ranges
+/// of its characters that are styled by them.
+/// If the text can't be parsed, it is returned as is, without any style.
+///
+/// # Example
+/// ```
+/// use television::utils::ansi::parse_ansi;
+/// use ratatui::style::{Color, Style};
+///
+/// let (text, styles) = parse_ansi("\x1b[31mred\x1b[0m plain");
+/// assert_eq!(text, "red plain");
+/// assert_eq!(styles, vec![(0, 3, Style::default().fg(Color::Red))]);
+/// ```
+pub fn parse_ansi(text: &str) -> (String, Vec<StyledRange>) {
+    let Ok(parsed) = text.into_text() else {
+        return (text.to_string(), Vec::new());
+    };
+    let mut stripped = String::with_capacity(text.len());
+    let mut styles = Vec::new();
+    let mut offset = 0;
+    for (i, line) in parsed.lines.iter().enumerate() {
+        if i > 0 {
+            stripped.push('\n');
+            offset += 1;
+        }
+        for span in &line.spans {
+            let len = u32::try_from(span.content.chars().count()).unwrap();
+            let style = normalize(line.style.patch(span.style));
+            if style != Style::default() && len > 0 {
+                styles.push((offset, offset + len, style));
+            }
+            stripped.push_str(&span.content);
+            offset += len;
+        }
+    }
+    (stripped, styles)
+}
+
+/// Only keep the colors and modifiers a style adds, since resetting them is
+/// what happens anyway when a range of characters isn't styled.
+fn normalize(style: Style) -> Style {
+    let mut normalized = Style::default().add_modifier(style.add_modifier);
+    if let Some(fg) = style.fg.filter(|c| *c != Color::Reset) {
+        normalized = normalized.fg(fg);
+    }
+    if let Some(bg) = style.bg.filter(|c| *c != Color::Reset) {
+        normalized = normalized.bg(bg);
+    }
+    normalized
+}
+
+/// Strip the ANSI escape sequences of the given text (see `parse_ansi`).
+pub fn strip_ansi(text: &str) -> String {
+    parse_ansi(text).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Modifier;
+
+    #[test]
+    fn test_parse_ansi_without_escape_codes() {
+        assert_eq!(parse_ansi("plain:text"), ("plain:text".into(), vec![]));
+    }
+
+    #[test]
+    fn test_parse_ansi_ranges_are_char_indices() {
+        let (text, styles) =
+            parse_ansi("é\x1b[1;32mgrün\x1b[0m:\x1b[34m1\x1b[0m");
+        assert_eq!(text, "égrün:1");
+        assert_eq!(
+            styles,
+            vec![
+                (
+                    1,
+                    5,
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD)
+                ),
+                (6, 7, Style::default().fg(Color::Blue)),
+            ]
+        );
+    }
+}
@@ -109,6 +109,28 @@ where
         self.known_keys.contains(key)
     }
 
+    /// Move the given item to the back of the buffer so that it's the last
+    /// one to be removed, e.g. when it was just used.
+    ///
+    /// Returns whether the item was in the buffer.
+    pub fn refresh(&mut self, item: &T) -> bool {
+        if !self.contains(item) {
+            return false;
+        }
+        if let Some(index) = self.ring_buffer.iter().position(|i| i == item) {
+            if let Some(item) = self.ring_buffer.remove(index) {
+                self.ring_buffer.push_back(item);
+            }
+        }
+        true
+    }
+
+    /// Remove all items from the buffer.
+    pub fn clear(&mut self) {
+        self.ring_buffer.clear();
+        self.known_keys.clear();
+    }
+
     /// Returns an iterator that goes from the back to the front of the buffer.
     pub fn back_to_front(&self) -> impl Iterator<Item = T> {
         self.ring_buffer.clone().into_iter().rev()
@@ -160,4 +182,22 @@ mod tests {
         assert!(ring_set.contains(&5));
         assert!(ring_set.contains(&6));
     }
+
+    #[test]
+    fn test_ring_set_refresh() {
+        let mut ring_set = RingSet::with_capacity(3);
+        ring_set.push(1);
+        ring_set.push(2);
+        ring_set.push(3);
+
+        // 1 becomes the most recent item so 2 is removed first
+        assert!(ring_set.refresh(&1));
+        assert!(!ring_set.refresh(&4));
+        assert_eq!(ring_set.push(4), Some(2));
+        assert_eq!(ring_set.back_to_front().collect::<Vec<_>>(), [4, 1, 3]);
+
+        ring_set.clear();
+        assert!(!ring_set.contains(&1));
+        assert_eq!(ring_set.push(5), None);
+    }
 }
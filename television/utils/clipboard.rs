@@ -25,6 +25,7 @@ SOFTWARE.
 use std::ffi::OsString;
 
 use crate::utils::rocell::RoCell;
+use anyhow::{anyhow, Result};
 use parking_lot::Mutex;
 
 pub static CLIPBOARD: RoCell<Clipboard> = RoCell::new();
@@ -79,8 +80,13 @@ impl Clipboard {
         self.content.lock().clone()
     }
 
+    /// Copy the given string to the system clipboard.
+    ///
+    /// This fails if a clipboard tool was found but none of them managed to
+    /// copy the string. If no tool is available, the terminal is relied on
+    /// instead (see `osc52`).
     #[cfg(unix)]
-    pub async fn set(&self, s: impl AsRef<std::ffi::OsStr>) {
+    pub async fn set(&self, s: impl AsRef<std::ffi::OsStr>) -> Result<()> {
         use std::{
             io::{stderr, BufWriter},
             process::Stdio,
@@ -104,6 +110,7 @@ impl Clipboard {
             ("xsel", &["-ib"]),
         ];
 
+        let mut failed = Vec::new();
         for (bin, args) in all {
             let cmd = Command::new(bin)
                 .args(args)
@@ -121,18 +128,26 @@ impl Clipboard {
                 .await
                 .is_err()
             {
+                failed.push(bin);
                 continue;
             }
             drop(stdin);
 
             if child.wait().await.is_ok_and(|s| s.success()) {
-                break;
+                return Ok(());
             }
+            failed.push(bin);
+        }
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("{} failed", failed.join(", ")))
         }
     }
 
+    /// Copy the given string to the system clipboard.
     #[cfg(windows)]
-    pub async fn set(&self, s: impl AsRef<std::ffi::OsStr>) {
+    pub async fn set(&self, s: impl AsRef<std::ffi::OsStr>) -> Result<()> {
         use clipboard_win::{formats, set_clipboard};
 
         let s = s.as_ref().to_owned();
@@ -141,8 +156,8 @@ impl Clipboard {
         tokio::task::spawn_blocking(move || {
             set_clipboard(formats::Unicode, s.to_string_lossy())
         })
-        .await
-        .ok();
+        .await?
+        .map_err(|e| anyhow!("{e}"))
     }
 }
 
@@ -5,7 +5,37 @@ use tracing::warn;
 
 use super::shell::Shell;
 
+/// Build a command that runs its argument through the user's shell.
+///
+/// On unix systems, the command is started in a new session which makes it
+/// the leader of its own process group. This allows killing the whole process
+/// tree (see `utils::process::ProcessTracker`) and keeps it from interfering
+/// with the terminal `tv` is running in.
 pub fn shell_command(interactive: bool) -> Command {
+    let mut cmd = foreground_shell_command(interactive);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: `setsid` is async-signal-safe and doesn't touch any memory
+        // shared with the parent process.
+        unsafe {
+            cmd.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+    }
+
+    cmd
+}
+
+/// Build a command that runs its argument through the user's shell while
+/// sharing `tv`'s session and terminal.
+///
+/// This is meant for commands the user interacts with (e.g. an editor) which
+/// need to be in the terminal's foreground process group.
+pub fn foreground_shell_command(interactive: bool) -> Command {
     let shell = Shell::from_env().unwrap_or_default();
     let mut cmd = Command::new(shell.executable());
 
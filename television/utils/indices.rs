@@ -83,10 +83,40 @@ pub fn truncate_highlighted_string<'a>(
     highlighted_ranges: &'a [(u32, u32)],
     max_width: u16,
 ) -> (String, Vec<(u32, u32)>) {
+    let (truncated, ranges, _) = truncate_highlighted_string_with_skip(
+        s,
+        highlighted_ranges,
+        max_width,
+    );
+    (truncated, ranges)
+}
+
+/// Same as `truncate_highlighted_string`, but also returns the number of
+/// characters cut off at the start of the string if it was truncated from
+/// the start (in which case an ellipsis took their place).
+///
+/// This can be used to shift other ranges of characters of the string (e.g.
+/// styles) the same way the highlighted ranges were.
+///
+/// # Example
+/// ```
+/// use television::utils::indices::truncate_highlighted_string_with_skip;
+///
+/// let (truncated, ranges, skipped) =
+///     truncate_highlighted_string_with_skip("hello world", &[(8, 9)], 6);
+/// assert_eq!(truncated, "…world");
+/// assert_eq!(ranges, vec![(3, 4)]);
+/// assert_eq!(skipped, Some(6));
+/// ```
+pub fn truncate_highlighted_string_with_skip(
+    s: &str,
+    highlighted_ranges: &[(u32, u32)],
+    max_width: u16,
+) -> (String, Vec<(u32, u32)>, Option<usize>) {
     let str_width = s.width();
 
     if str_width <= max_width as usize {
-        return (s.to_string(), highlighted_ranges.to_vec());
+        return (s.to_string(), highlighted_ranges.to_vec(), None);
     }
 
     let last_highlighted_char_index =
@@ -116,6 +146,7 @@ pub fn truncate_highlighted_string<'a>(
                 .collect::<String>()
                 + ELLIPSIS,
             highlighted_ranges.to_vec(),
+            None,
         );
     }
 
@@ -157,6 +188,7 @@ pub fn truncate_highlighted_string<'a>(
                 })
                 .filter(|(start, end)| start != end)
                 .collect(),
+            Some(chars_to_skip),
         );
     }
 
@@ -206,6 +238,7 @@ pub fn truncate_highlighted_string<'a>(
             })
             .filter(|(start, end)| start != end)
             .collect(),
+        Some(chars_to_skip),
     )
 }
 
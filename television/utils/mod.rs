@@ -1,3 +1,4 @@
+pub mod ansi;
 pub mod cache;
 pub mod clipboard;
 pub mod command;
@@ -6,6 +7,7 @@ pub mod hashmaps;
 pub mod indices;
 pub mod input;
 pub mod metadata;
+pub mod process;
 pub mod rocell;
 pub mod shell;
 pub mod stdin;
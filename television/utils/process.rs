@@ -0,0 +1,254 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
+use tracing::debug;
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    /// The ids of the currently tracked processes.
+    ///
+    /// Each of these processes is the leader of its own process group (see
+    /// `shell_command`) so that killing the group also takes care of any
+    /// grandchildren it may have spawned (e.g. pipelines).
+    pids: FxHashSet<u32>,
+    /// Whether the tracker was shut down.
+    ///
+    /// A closed tracker immediately kills any process it is asked to track.
+    closed: bool,
+}
+
+/// Keeps track of the child processes spawned on behalf of a channel or a
+/// previewer so that they can be terminated when they're no longer needed.
+///
+/// The tracker is cheap to clone and all clones share the same state, which
+/// means it can be handed over to background tasks that spawn processes.
+///
+/// # Example
+/// ```ignore
+/// use television::utils::{command::shell_command, process::ProcessTracker};
+///
+/// let tracker = ProcessTracker::default();
+/// let mut child = shell_command(false).arg("sleep 10").spawn().unwrap();
+/// tracker.register(child.id());
+///
+/// // kills `sleep` along with any process it might have spawned
+/// tracker.shutdown();
+/// let _ = child.wait();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProcessTracker {
+    state: Arc<Mutex<TrackerState>>,
+}
+
+impl ProcessTracker {
+    /// Start tracking the process with the given id.
+    ///
+    /// If the tracker was already shut down, the process is killed right away.
+    pub fn register(&self, pid: u32) {
+        let mut state = self.state.lock();
+        if state.closed {
+            debug!("Tracker is closed, killing process group {}", pid);
+            kill_process_group(pid);
+            return;
+        }
+        state.pids.insert(pid);
+    }
+
+    /// Stop tracking the process with the given id.
+    ///
+    /// This should be called once the process has exited and returns whether
+    /// the process was still being tracked, i.e. `false` means the process
+    /// was killed by the tracker in the meantime.
+    pub fn unregister(&self, pid: u32) -> bool {
+        self.state.lock().pids.remove(&pid)
+    }
+
+    /// Kill the process group of the given process and stop tracking it.
+    ///
+    /// Processes that aren't tracked (anymore) are left alone since their
+    /// pid may have been reused in the meantime.
+    pub fn kill(&self, pid: u32) {
+        if self.state.lock().pids.remove(&pid) {
+            debug!("Killing process group {}", pid);
+            kill_process_group(pid);
+        }
+    }
+
+    /// Kill all currently tracked process groups.
+    ///
+    /// The tracker can still be used to track new processes afterwards.
+    pub fn kill_all(&self) {
+        let pids = std::mem::take(&mut self.state.lock().pids);
+        for pid in pids {
+            debug!("Killing process group {}", pid);
+            kill_process_group(pid);
+        }
+    }
+
+    /// Kill all currently tracked process groups and close the tracker.
+    ///
+    /// Any process registered after this will be killed immediately.
+    pub fn shutdown(&self) {
+        self.state.lock().closed = true;
+        self.kill_all();
+    }
+
+    /// The number of processes currently being tracked.
+    pub fn len(&self) -> usize {
+        self.state.lock().pids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Kill the process group led by the process with the given id.
+///
+/// A pid of 0 is ignored, as `killpg` would take it to mean `tv`'s own
+/// process group.
+#[cfg(unix)]
+pub fn kill_process_group(pid: u32) {
+    let Ok(pgid) = libc::pid_t::try_from(pid) else {
+        return;
+    };
+    if pgid == 0 {
+        return;
+    }
+    // SAFETY: `killpg` has no memory safety requirements, at worst it fails
+    // with `ESRCH` if the group no longer exists.
+    unsafe {
+        libc::killpg(pgid, libc::SIGKILL);
+    }
+}
+
+/// Kill the process tree rooted at the process with the given id.
+#[cfg(not(unix))]
+pub fn kill_process_group(pid: u32) {
+    if pid == 0 {
+        return;
+    }
+    let _ = std::process::Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::utils::command::shell_command;
+    use std::{
+        io::{BufRead, BufReader},
+        process::Stdio,
+        time::{Duration, Instant},
+    };
+
+    /// Whether the process with the given pid is still running.
+    fn is_alive(pid: u32) -> bool {
+        // orphaned processes might linger around as zombies for a while
+        // before being reaped, which still counts as dead for our purposes
+        if let Ok(stat) = std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        {
+            return !stat.contains(") Z ");
+        }
+        // SAFETY: signal 0 only checks for the existence of the process.
+        unsafe { libc::kill(libc::pid_t::try_from(pid).unwrap(), 0) == 0 }
+    }
+
+    /// Waits for the process with the given pid to die and returns whether
+    /// it did so in a reasonable amount of time.
+    fn wait_for_death(pid: u32) -> bool {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(2) {
+            if !is_alive(pid) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    /// Spawns a shell that starts a background `sleep` and returns the shell
+    /// process along with the pid of that grandchild.
+    fn spawn_with_grandchild() -> (std::process::Child, u32) {
+        let mut child = shell_command(false)
+            .arg("sleep 30 & echo $!; wait")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        (child, line.trim().parse().unwrap())
+    }
+
+    #[test]
+    fn test_kill_all_kills_grandchildren() {
+        let tracker = ProcessTracker::default();
+        let (mut child, grandchild) = spawn_with_grandchild();
+        tracker.register(child.id());
+        assert!(is_alive(grandchild));
+
+        tracker.kill_all();
+        let _ = child.wait();
+
+        assert!(wait_for_death(grandchild));
+        assert!(tracker.is_empty());
+        assert!(!tracker.unregister(child.id()));
+    }
+
+    #[test]
+    fn test_kill_leaves_other_processes_alone() {
+        let tracker = ProcessTracker::default();
+        let (mut killed, killed_grandchild) = spawn_with_grandchild();
+        let (mut kept, kept_grandchild) = spawn_with_grandchild();
+        tracker.register(killed.id());
+        tracker.register(kept.id());
+
+        tracker.kill(killed.id());
+        let _ = killed.wait();
+
+        assert!(wait_for_death(killed_grandchild));
+        assert!(is_alive(kept_grandchild));
+        assert_eq!(tracker.len(), 1);
+
+        tracker.shutdown();
+        let _ = kept.wait();
+        assert!(wait_for_death(kept_grandchild));
+    }
+
+    #[test]
+    fn test_register_after_shutdown_kills_process() {
+        let tracker = ProcessTracker::default();
+        tracker.shutdown();
+
+        let (mut child, grandchild) = spawn_with_grandchild();
+        tracker.register(child.id());
+        let _ = child.wait();
+
+        assert!(wait_for_death(grandchild));
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_kill_process_group_ignores_pid_zero() {
+        // this would kill the test runner otherwise
+        kill_process_group(0);
+        assert!(is_alive(std::process::id()));
+    }
+
+    #[test]
+    fn test_unregister_finished_process() {
+        let tracker = ProcessTracker::default();
+        let mut child = shell_command(false).arg("true").spawn().unwrap();
+        tracker.register(child.id());
+        let _ = child.wait();
+
+        assert!(tracker.unregister(child.id()));
+        assert!(tracker.is_empty());
+    }
+}
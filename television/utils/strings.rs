@@ -566,8 +566,29 @@ pub fn shrink_with_ellipsis(s: &str, max_length: usize) -> String {
 
 pub static CMD_RE: &Lazy<Regex> = regex!(r"\{(\d+)\}");
 
+/// Quote a string so that it's passed as a single argument by the shell.
+///
+/// Single quotes inside the string are escaped so that they can't terminate
+/// the quoted string early.
+///
+/// # Example
+/// ```
+/// use television::utils::strings::shell_quote;
+///
+/// assert_eq!(shell_quote("foo bar"), "'foo bar'");
+/// assert_eq!(shell_quote("don't"), r"'don'\''t'");
+/// ```
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
 /// Formats a prototype string with the given template and source strings.
 ///
+/// This only handles the `{}` and `{N}` placeholders, which refer to an
+/// entry. A channel's source command is formatted with `format_query`
+/// instead since it isn't run against an entry and may contain those
+/// placeholders literally (e.g. `find . -exec ls {} \;`).
+///
 /// # Example
 /// ```
 /// use television::utils::strings::format_string;
@@ -581,26 +602,138 @@ pub static CMD_RE: &Lazy<Regex> = regex!(r"\{(\d+)\}");
 /// ```
 pub fn format_string(template: &str, source: &str, delimiter: &str) -> String {
     let parts = source.split(delimiter).collect::<Vec<&str>>();
+    format_command(template, |placeholder| match placeholder {
+        QUERY_PLACEHOLDER => None,
+        // `{}` doesn't have an index and refers to the whole source
+        "{}" => Some(source),
+        // this unwrap is safe because of the regex pattern
+        index => Some(
+            parts
+                .get(index[1..index.len() - 1].parse::<usize>().unwrap())
+                .unwrap_or(&""),
+        ),
+    })
+}
+
+/// Matches the placeholders of a command: `{}`, `{N}` and `{q}`.
+static COMMAND_PLACEHOLDER_RE: &Lazy<Regex> = regex!(r"\{(?:\d*|q)\}");
+
+/// Replaces the placeholders of a command with the shell-quoted value
+/// `value` gives for them, leaving those it gives none for as is.
+fn format_command<'a>(
+    template: &str,
+    value: impl Fn(&str) -> Option<&'a str>,
+) -> String {
+    COMMAND_PLACEHOLDER_RE
+        .replace_all(template, |caps: &regex::Captures| {
+            value(&caps[0]).map_or_else(|| caps[0].to_string(), shell_quote)
+        })
+        .into_owned()
+}
 
-    let mut formatted_string =
-        template.replace("{}", format!("'{}'", source).as_str());
+/// Matches the `{}` and `{N}` placeholders of a template (see
+/// `format_template`).
+pub static PLACEHOLDER_RE: &Lazy<Regex> = regex!(r"\{(\d*)\}");
 
-    formatted_string = CMD_RE
-        .replace_all(&formatted_string, |caps: &regex::Captures| {
-            let index =
-                // these unwraps are safe because of the regex pattern
-                caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
-            format!("'{}'", parts.get(index).unwrap_or(&""))
+/// Formats a template with the given source string the same way
+/// `format_string` does, but without quoting what replaces the
+/// placeholders, e.g. to build the text displayed or output for an entry.
+///
+/// # Example
+/// ```
+/// use television::utils::strings::format_template;
+///
+/// let formatted = format_template("{1} ({0})", "abc:image:latest", ":");
+/// assert_eq!(formatted, "image (abc)");
+/// assert_eq!(format_template("[{}]", "a:b", ":"), "[a:b]");
+/// ```
+pub fn format_template(
+    template: &str,
+    source: &str,
+    delimiter: &str,
+) -> String {
+    let parts = source.split(delimiter).collect::<Vec<&str>>();
+    PLACEHOLDER_RE
+        .replace_all(template, |caps: &regex::Captures| {
+            // `{}` doesn't have an index and refers to the whole source
+            match caps[1].parse::<usize>() {
+                Ok(index) => parts.get(index).unwrap_or(&""),
+                Err(_) => source,
+            }
         })
-        .to_string();
+        .into_owned()
+}
+
+/// The placeholder for the user's query in a channel's source command.
+pub const QUERY_PLACEHOLDER: &str = "{q}";
 
-    formatted_string
+/// Formats a source command template with the user's query.
+///
+/// This goes through the same formatting as `format_string`, except that
+/// only `{q}` is replaced: any `{}` or `{N}` in the source command is left
+/// for the command itself to interpret.
+///
+/// # Example
+/// ```
+/// use television::utils::strings::format_query;
+///
+/// let formatted = format_query("rg --line-number {q}", "foo bar");
+/// assert_eq!(formatted, "rg --line-number 'foo bar'");
+/// ```
+pub fn format_query(template: &str, query: &str) -> String {
+    format_command(template, |placeholder| {
+        (placeholder == QUERY_PLACEHOLDER).then_some(query)
+    })
+}
+
+/// The placeholder for a list of entries, i.e. the selected entries in a
+/// custom action's command or the entries sent to a channel in its source
+/// command.
+pub const SELECTED_ENTRIES_PLACEHOLDER: &str = "{+}";
+
+/// Formats a template with a list of entries, each of them being quoted
+/// separately.
+///
+/// # Example
+/// ```
+/// use television::utils::strings::format_entries;
+///
+/// let formatted = format_entries("rm {+}", &["a", "b c"]);
+/// assert_eq!(formatted, "rm 'a' 'b c'");
+/// ```
+pub fn format_entries<S: AsRef<str>>(template: &str, entries: &[S]) -> String {
+    let quoted = entries
+        .iter()
+        .map(|e| shell_quote(e.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    template.replace(SELECTED_ENTRIES_PLACEHOLDER, &quoted)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_string_escapes_single_quotes() {
+        assert_eq!(
+            format_string("cat {} {0}", "it's:here", ":"),
+            r"cat 'it'\''s:here' 'it'\''s'"
+        );
+    }
+
+    #[test]
+    fn test_format_query() {
+        assert_eq!(format_query("rg {q}", ""), "rg ''");
+        assert_eq!(format_query("rg -- {q} .", "a b"), "rg -- 'a b' .");
+        assert_eq!(format_query("fd {}", "a"), "fd {}");
+        assert_eq!(
+            format_query("rg {q}", "it's {}"),
+            format_string("rg {}", "it's {}", ":")
+        );
+        assert_eq!(format_string("cat {} {q}", "a", ":"), "cat 'a' {q}");
+    }
+
     fn test_next_char_boundary(input: &str, start: usize, expected: usize) {
         let actual = next_char_boundary(input, start);
         assert_eq!(actual, expected);
@@ -1,18 +1,33 @@
 use std::{collections::HashSet, path::PathBuf, time::Duration};
 
+use rustc_hash::FxHashMap;
 use television::{
     action::Action,
     app::{App, AppOptions},
-    channels::prototypes::{Cable, ChannelPrototype},
+    channels::{
+        actions::{ActionSpec, ExecutionMode},
+        prototypes::{Cable, ChannelPrototype},
+    },
     config::default_config_from_file,
+    event::Key,
+    television::MatchingMode,
 };
 use tokio::{task::JoinHandle, time::timeout};
 
+mod support;
+#[cfg(unix)]
+use support::wait_for_death;
+
 /// Default timeout for tests.
 ///
 /// This is kept quite high to avoid flakiness in CI.
 const DEFAULT_TIMEOUT: Duration = Duration::from_millis(100);
 
+/// The data directory used by the apps set up for the tests.
+fn test_data_dir() -> PathBuf {
+    std::env::temp_dir().join("television-tests")
+}
+
 /// Sets up an app with a file channel and default config.
 ///
 /// Returns a tuple containing the app's `JoinHandle` and the action channel's
@@ -27,6 +42,20 @@ fn setup_app(
 ) -> (
     JoinHandle<television::app::AppOutput>,
     tokio::sync::mpsc::UnboundedSender<Action>,
+) {
+    setup_app_with_cable(channel_prototype, select_1, exact, &Cable::default())
+}
+
+/// Same as `setup_app` but with the given cable channels available through
+/// the remote control.
+fn setup_app_with_cable(
+    channel_prototype: Option<ChannelPrototype>,
+    select_1: bool,
+    exact: bool,
+    cable: &Cable,
+) -> (
+    JoinHandle<television::app::AppOutput>,
+    tokio::sync::mpsc::UnboundedSender<Action>,
 ) {
     let chan: ChannelPrototype = channel_prototype.unwrap_or_else(|| {
         let target_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -38,6 +67,8 @@ fn setup_app(
     let mut config = default_config_from_file().unwrap();
     // this speeds up the tests
     config.application.tick_rate = 100.0;
+    // keep the tests away from the user's data (e.g. query history)
+    config.application.data_dir = test_data_dir();
     let input = None;
 
     let options = AppOptions::new(
@@ -47,7 +78,7 @@ fn setup_app(
         false,
         config.application.tick_rate,
     );
-    let mut app = App::new(&chan, config, input, options, &Cable::default());
+    let mut app = App::new(&chan, config, input, options, cable);
 
     // retrieve the app's action channel handle in order to send a quit action
     let tx = app.action_tx.clone();
@@ -262,3 +293,444 @@ async fn test_app_does_not_exit_when_select_1_and_more_than_one_result() {
 
     assert!(output.is_err());
 }
+
+/// Returns a channel prototype whose source spawns a long running background
+/// process and writes that process' pid to the given file.
+#[cfg(unix)]
+fn long_running_prototype(pid_file: &std::path::Path) -> ChannelPrototype {
+    ChannelPrototype::new(
+        "long_running",
+        &format!(
+            "sleep 30 & echo $! > '{}'; echo entry; wait",
+            pid_file.display()
+        ),
+        false,
+        None,
+    )
+}
+
+#[cfg(unix)]
+fn read_pid(pid_file: &std::path::Path) -> u32 {
+    for _ in 0..100 {
+        if let Ok(content) = std::fs::read_to_string(pid_file) {
+            if let Ok(pid) = content.trim().parse() {
+                return pid;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    panic!("source command did not write its pid in time");
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_kills_source_processes_on_exit() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let (f, tx) =
+        setup_app(Some(long_running_prototype(&pid_file)), false, false);
+    let pid = read_pid(&pid_file);
+
+    tx.send(Action::Quit).unwrap();
+    timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    assert!(wait_for_death(pid), "source process outlived the app");
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_kills_source_processes_on_channel_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let (f, tx) =
+        setup_app(Some(long_running_prototype(&pid_file)), false, false);
+    let pid = read_pid(&pid_file);
+
+    // zap to another channel using the remote control
+    tx.send(Action::ToggleRemoteControl).unwrap();
+    for c in "env".chars() {
+        tx.send(Action::AddInputChar(c)).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ConfirmSelection).unwrap();
+
+    assert!(wait_for_death(pid), "source process outlived its channel");
+    assert!(!f.is_finished());
+
+    f.abort();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_reload_source_keeps_selected_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("entries");
+    std::fs::write(&file, "b\nc\n").unwrap();
+    let prototype = ChannelPrototype::new(
+        "reloadable",
+        &format!("cat '{}'", file.display()),
+        false,
+        None,
+    );
+    let (f, tx) = setup_app(Some(prototype), false, false);
+
+    // tick a few times to get the results
+    for _ in 0..10 {
+        tx.send(Action::Tick).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::SelectNextEntry).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+
+    // a new entry shows up before the selected one
+    std::fs::write(&file, "a\nb\nc\n").unwrap();
+    tx.send(Action::ReloadSource).unwrap();
+    for _ in 0..10 {
+        tx.send(Action::Tick).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    tx.send(Action::ConfirmSelection).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    assert_eq!(
+        output
+            .selected_entries
+            .unwrap()
+            .drain()
+            .next()
+            .unwrap()
+            .name,
+        "c"
+    );
+}
+
+/// Tick the app a few times to let it load the channel's entries.
+fn tick_until_loaded(tx: &tokio::sync::mpsc::UnboundedSender<Action>) {
+    for _ in 0..10 {
+        tx.send(Action::Tick).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(50));
+}
+
+/// Returns a channel prototype with a single `action` custom action running
+/// the given command in the given mode.
+fn prototype_with_action(
+    command: &str,
+    mode: ExecutionMode,
+) -> ChannelPrototype {
+    ChannelPrototype::new(
+        "actions",
+        "echo 'entry 1'; echo 'entry 2'",
+        false,
+        None,
+    )
+    .with_action("action", ActionSpec::new(command, Key::Alt('a'), mode))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_custom_action_execute() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("output");
+    let (f, tx) = setup_app(
+        Some(prototype_with_action(
+            &format!("echo {{+}} > '{}'", file.display()),
+            ExecutionMode::Execute,
+        )),
+        false,
+        false,
+    );
+    tick_until_loaded(&tx);
+
+    // select both entries
+    tx.send(Action::ToggleSelectionDown).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ToggleSelectionDown).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::CustomAction("action".to_string())).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "entry 1 entry 2\n");
+    // the app keeps running once the command exited
+    assert!(!f.is_finished());
+
+    f.abort();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_custom_action_fork() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("output");
+    let (f, tx) = setup_app(
+        Some(prototype_with_action(
+            &format!("echo {{}} > '{}'", file.display()),
+            ExecutionMode::Fork,
+        )),
+        false,
+        false,
+    );
+    tick_until_loaded(&tx);
+
+    tx.send(Action::CustomAction("action".to_string())).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "entry 1\n");
+    assert!(!f.is_finished());
+
+    f.abort();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_custom_action_become() {
+    let (f, tx) = setup_app(
+        Some(prototype_with_action("vim {}", ExecutionMode::Become)),
+        false,
+        false,
+    );
+    tick_until_loaded(&tx);
+
+    tx.send(Action::CustomAction("action".to_string())).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    assert_eq!(output.become_command, Some("vim 'entry 1'".to_string()));
+    assert!(output.selected_entries.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_send_to_channel() {
+    let source = ChannelPrototype::new(
+        "source",
+        "echo 'entry 1'; echo 'entry 2'; echo 'entry 3'",
+        false,
+        None,
+    );
+    // reads the entries it is sent from stdin
+    let target = ChannelPrototype::new("upper", "tr a-z A-Z", false, None);
+    let cable = Cable(FxHashMap::from_iter([
+        (source.name.clone(), source.clone()),
+        (target.name.clone(), target),
+    ]));
+    let (f, tx) = setup_app_with_cable(Some(source), false, false, &cable);
+    tick_until_loaded(&tx);
+
+    // send the first two entries over to the `upper` channel
+    tx.send(Action::ToggleSelectionDown).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ToggleSelectionDown).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ToggleSendToChannel).unwrap();
+    for c in "upper".chars() {
+        tx.send(Action::AddInputChar(c)).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ConfirmSelection).unwrap();
+    tick_until_loaded(&tx);
+
+    tx.send(Action::ToggleSelectionDown).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ToggleSelectionDown).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ToggleSelectionDown).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ConfirmSelection).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    assert_eq!(
+        output
+            .selected_entries
+            .unwrap()
+            .drain()
+            .map(|e| e.name)
+            .collect::<HashSet<_>>(),
+        HashSet::from(["ENTRY 1".to_string(), "ENTRY 2".to_string()])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_go_back_restores_previous_channel() {
+    let dir = tempfile::tempdir().unwrap();
+    let runs = dir.path().join("runs");
+    let first = ChannelPrototype::new(
+        "first",
+        &format!(
+            "echo run >> '{}'; echo 'entry 1'; echo 'entry 2'",
+            runs.display()
+        ),
+        false,
+        None,
+    );
+    let second = ChannelPrototype::new("second", "echo 'other'", false, None);
+    let cable = Cable(FxHashMap::from_iter([
+        (first.name.clone(), first.clone()),
+        (second.name.clone(), second),
+    ]));
+    let (f, tx) = setup_app_with_cable(Some(first), false, false, &cable);
+    tick_until_loaded(&tx);
+
+    // filter the first channel down to `entry 2` and select it
+    tx.send(Action::AddInputChar('2')).unwrap();
+    tx.send(Action::ToggleSelectionDown).unwrap();
+    tick_until_loaded(&tx);
+
+    // zap to the second channel, then back and forth again
+    tx.send(Action::ToggleRemoteControl).unwrap();
+    for c in "second".chars() {
+        tx.send(Action::AddInputChar(c)).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(50));
+    tx.send(Action::ConfirmSelection).unwrap();
+    tick_until_loaded(&tx);
+    tx.send(Action::GoBack).unwrap();
+    tx.send(Action::GoForward).unwrap();
+    tx.send(Action::GoBack).unwrap();
+    tick_until_loaded(&tx);
+    tx.send(Action::ConfirmSelection).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    assert_eq!(
+        output
+            .selected_entries
+            .unwrap()
+            .drain()
+            .map(|e| e.name)
+            .collect::<Vec<_>>(),
+        vec!["entry 2".to_string()]
+    );
+    // going back didn't re-run the first channel's source
+    assert_eq!(std::fs::read_to_string(&runs).unwrap(), "run\n");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_previous_query() {
+    let prototype = ChannelPrototype::new(
+        "query-history",
+        "echo 'alpha'; echo 'beta'",
+        false,
+        None,
+    );
+    let _ = std::fs::remove_file(
+        test_data_dir().join("history").join("query-history"),
+    );
+
+    // submit a query
+    let (f, tx) = setup_app(Some(prototype.clone()), false, false);
+    tick_until_loaded(&tx);
+    for c in "bet".chars() {
+        tx.send(Action::AddInputChar(c)).unwrap();
+    }
+    tick_until_loaded(&tx);
+    tx.send(Action::ConfirmSelection).unwrap();
+    timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    // bring it back in a new session
+    let (f, tx) = setup_app(Some(prototype), false, false);
+    tick_until_loaded(&tx);
+    tx.send(Action::PreviousQuery).unwrap();
+    tick_until_loaded(&tx);
+    tx.send(Action::ConfirmSelection).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    assert_eq!(
+        output
+            .selected_entries
+            .unwrap()
+            .drain()
+            .next()
+            .unwrap()
+            .name,
+        "beta"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_regex_matching_mode() {
+    let prototype = ChannelPrototype::new(
+        "regex",
+        "echo 'foo.rs'; echo 'fo.rs'; echo 'foo.txt'",
+        false,
+        None,
+    )
+    .with_matching_mode(Some(MatchingMode::Regex));
+    let (f, tx) = setup_app(Some(prototype), false, false);
+    tick_until_loaded(&tx);
+
+    for c in r"^fo\.".chars() {
+        tx.send(Action::AddInputChar(c)).unwrap();
+    }
+    tick_until_loaded(&tx);
+    tx.send(Action::ConfirmSelection).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    assert_eq!(
+        output
+            .selected_entries
+            .unwrap()
+            .drain()
+            .next()
+            .unwrap()
+            .name,
+        "fo.rs"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn test_app_cycle_matching_mode() {
+    let prototype =
+        ChannelPrototype::new("cycle", "echo 'Foo'; echo 'foo'", false, None);
+    let (f, tx) = setup_app(Some(prototype), false, false);
+    tick_until_loaded(&tx);
+
+    for c in "foo".chars() {
+        tx.send(Action::AddInputChar(c)).unwrap();
+    }
+    // fuzzy -> substring -> prefix -> case-sensitive
+    for _ in 0..3 {
+        tx.send(Action::CycleMatchingMode).unwrap();
+    }
+    tick_until_loaded(&tx);
+    tx.send(Action::ConfirmSelection).unwrap();
+
+    let output = timeout(DEFAULT_TIMEOUT, f)
+        .await
+        .expect("app did not finish within the default timeout")
+        .unwrap();
+
+    assert_eq!(
+        output
+            .selected_entries
+            .unwrap()
+            .drain()
+            .next()
+            .unwrap()
+            .name,
+        "foo"
+    );
+}
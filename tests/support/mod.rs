@@ -0,0 +1,28 @@
+//! Helpers for the tests that check on the processes spawned by `tv`.
+#![cfg(unix)]
+
+use std::time::{Duration, Instant};
+
+/// Whether the process with the given pid is still running.
+pub fn is_alive(pid: u32) -> bool {
+    // orphaned processes might linger around as zombies for a while before
+    // being reaped, which still counts as dead for our purposes
+    if let Ok(stat) = std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+        return !stat.contains(") Z ");
+    }
+    // SAFETY: signal 0 only checks for the existence of the process.
+    unsafe { libc::kill(libc::pid_t::try_from(pid).unwrap(), 0) == 0 }
+}
+
+/// Waits for the process with the given pid to die and returns whether it
+/// did so in a reasonable amount of time.
+pub fn wait_for_death(pid: u32) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(2) {
+        if !is_alive(pid) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}